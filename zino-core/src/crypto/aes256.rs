@@ -13,12 +13,29 @@ const NONCE_SIZE: usize = 12;
 
 /// Encrypts the plaintext using `AES-GCM-SIV`.
 pub(crate) fn encrypt(plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>, Error> {
-    let cipher = Aes256GcmSiv::new(GenericArray::from_slice(&padded_key(key)));
-
     let mut rng = rand::thread_rng();
     let mut bytes = [0u8; NONCE_SIZE];
     rng.fill(&mut bytes);
+    encrypt_with_nonce(plaintext, key, bytes)
+}
 
+/// Encrypts the plaintext using `AES-GCM-SIV` with a nonce derived from the plaintext,
+/// so that the same plaintext always produces the same ciphertext.
+#[cfg(feature = "orm")]
+pub(crate) fn encrypt_deterministic(plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>, Error> {
+    let digest = super::digest(&[key, plaintext].concat());
+    let mut bytes = [0u8; NONCE_SIZE];
+    bytes.copy_from_slice(&digest[..NONCE_SIZE]);
+    encrypt_with_nonce(plaintext, key, bytes)
+}
+
+/// Encrypts the plaintext using `AES-GCM-SIV` with the nonce.
+fn encrypt_with_nonce(
+    plaintext: &[u8],
+    key: &[u8],
+    bytes: [u8; NONCE_SIZE],
+) -> Result<Vec<u8>, Error> {
+    let cipher = Aes256GcmSiv::new(GenericArray::from_slice(&padded_key(key)));
     let nonce = Nonce::from_slice(&bytes);
     let mut ciphertext = cipher
         .encrypt(nonce, plaintext)
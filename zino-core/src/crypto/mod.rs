@@ -14,6 +14,9 @@ cfg_if::cfg_if! {
         pub(crate) use sm3::{derive_key, digest};
         pub(crate) use sm4::{decrypt, encrypt};
 
+        #[cfg(feature = "orm")]
+        pub(crate) use sm4::encrypt_deterministic;
+
         /// Digest type.
         pub(crate) type Digest = ::sm3::Sm3;
     } else {
@@ -21,6 +24,9 @@ cfg_if::cfg_if! {
         mod sha256;
 
         pub(crate) use aes256::{decrypt, encrypt};
+
+        #[cfg(feature = "orm")]
+        pub(crate) use aes256::encrypt_deterministic;
         pub(crate) use sha256::{derive_key, digest};
 
         /// Digest type.
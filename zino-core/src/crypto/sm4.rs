@@ -17,7 +17,25 @@ pub(crate) fn encrypt(plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>, Error> {
     let mut rng = rand::thread_rng();
     let mut nonce = [0u8; NONCE_SIZE];
     rng.fill(&mut nonce);
+    encrypt_with_nonce(plaintext, key, nonce)
+}
+
+/// Encrypts the plaintext using `SM4` with a nonce derived from the plaintext,
+/// so that the same plaintext always produces the same ciphertext.
+#[cfg(feature = "orm")]
+pub(crate) fn encrypt_deterministic(plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>, Error> {
+    let digest = super::digest(&[key, plaintext].concat());
+    let mut nonce = [0u8; NONCE_SIZE];
+    nonce.copy_from_slice(&digest[..NONCE_SIZE]);
+    encrypt_with_nonce(plaintext, key, nonce)
+}
 
+/// Encrypts the plaintext using `SM4` with the nonce.
+fn encrypt_with_nonce(
+    plaintext: &[u8],
+    key: &[u8],
+    nonce: [u8; NONCE_SIZE],
+) -> Result<Vec<u8>, Error> {
     let mut buf = plaintext.to_vec();
     let key = padded_key(key).into();
     let iv = nonce.into();
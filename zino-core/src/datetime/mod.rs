@@ -5,7 +5,7 @@ use chrono::{
     format::ParseError, Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime,
     SecondsFormat, TimeZone, Timelike, Utc, Weekday,
 };
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     fmt,
     ops::{Add, AddAssign, Sub, SubAssign},
@@ -26,7 +26,7 @@ pub use time::Time;
 type LocalDateTime = chrono::DateTime<Local>;
 
 /// A wrapper type for [`chrono::DateTime<Local>`](chrono::DateTime).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DateTime(LocalDateTime);
 
 impl DateTime {
@@ -636,6 +636,15 @@ impl Serialize for DateTime {
     }
 }
 
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse::<DateTime>()
+            .or_else(|_| LocalDateTime::from_str(&s).map(Self))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 impl From<Date> for DateTime {
     fn from(d: Date) -> Self {
         let dt = NaiveDateTime::new(d.into(), NaiveTime::default());
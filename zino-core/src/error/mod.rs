@@ -61,7 +61,16 @@ impl Error {
     pub fn from_error(err: impl error::Error) -> Self {
         Self {
             message: err.to_string().into(),
-            source: err.source().map(|err| Box::new(Self::new(err.to_string()))),
+            source: err.source().map(|err| Box::new(Self::from_source(err))),
+            context: None,
+        }
+    }
+
+    /// Creates a new instance from the source error recursively, preserving the source chain.
+    fn from_source(err: &dyn error::Error) -> Self {
+        Self {
+            message: err.to_string().into(),
+            source: err.source().map(|err| Box::new(Self::from_source(err))),
             context: None,
         }
     }
@@ -142,7 +151,7 @@ impl<E: error::Error + Send + 'static> From<E> for Error {
     fn from(err: E) -> Self {
         Self {
             message: err.to_string().into(),
-            source: err.source().map(|err| Box::new(Self::new(err.to_string()))),
+            source: err.source().map(|err| Box::new(Self::from_source(err))),
             context: Some(Box::new(err)),
         }
     }
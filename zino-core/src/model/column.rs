@@ -140,6 +140,12 @@ impl<'a> Column<'a> {
         self.reference.as_ref()
     }
 
+    /// Returns the type discriminator column if the column is a polymorphic reference.
+    #[inline]
+    pub fn reference_type(&self) -> Option<&str> {
+        self.extra.get_str("reference_type")
+    }
+
     /// Returns the comment.
     #[inline]
     pub fn comment(&self) -> Option<&'a str> {
@@ -225,6 +231,13 @@ impl<'a> Column<'a> {
         )
     }
 
+    /// Returns `true` if the column is a geographic point `Option<(f64, f64)>`
+    /// with the longitude and latitude.
+    #[inline]
+    pub fn is_point_type(&self) -> bool {
+        self.type_name() == "Option<(f64, f64)>"
+    }
+
     /// Returns `true` if the column has a type of `DateTime`, `Date`, `Time`,
     /// or `String` with a format `date-time`, `date`, `time`.
     pub fn is_datetime_type(&self) -> bool {
@@ -293,6 +306,14 @@ impl<'a> Column<'a> {
                     Schema::Int
                 }
             }
+            "Option<(f64, f64)>" => {
+                let point_schema = Schema::Array(Box::new(Schema::Double));
+                if let Ok(union_schema) = UnionSchema::new(vec![Schema::Null, point_schema]) {
+                    Schema::Union(union_schema)
+                } else {
+                    Schema::Array(Box::new(Schema::Double))
+                }
+            }
             "Map" => Schema::Map(Box::new(Schema::Ref {
                 name: Name {
                     name: "Json".to_owned(),
@@ -454,6 +475,15 @@ impl<'a> Column<'a> {
                 definition.upsert("type", "array");
                 definition.upsert("items", items);
             }
+            "Option<(f64, f64)>" => {
+                let mut items = Map::new();
+                items.upsert("type", "number");
+                items.upsert("format", "double");
+                definition.upsert("type", "array");
+                definition.upsert("items", items);
+                definition.upsert("minItems", 2);
+                definition.upsert("maxItems", 2);
+            }
             "Map" => {
                 definition.upsert("type", "object");
             }
@@ -640,6 +670,12 @@ impl<'a> Column<'a> {
             "Vec<u32>" => self.mock_integer_array::<u32>().into(),
             "Vec<u64>" => self.mock_integer_array::<u64>().into(),
             "Vec<String>" => self.mock_string_array().into(),
+            "Option<(f64, f64)>" => {
+                let mut rng = thread_rng();
+                let lng = rng.gen_range(-180.0..=180.0);
+                let lat = rng.gen_range(-90.0..=90.0);
+                random::<bool>().then(|| vec![lng, lat]).into()
+            }
             _ => JsonValue::Null,
         }
     }
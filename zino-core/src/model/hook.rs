@@ -100,12 +100,28 @@ pub trait ModelHooks: Model {
         Ok(())
     }
 
+    /// A hook running before accessing a model by the primary key,
+    /// which can be used to enforce the access control.
+    #[inline]
+    async fn before_access(&self, _extension: Option<&Self::Extension>) -> Result<(), Error> {
+        Ok(())
+    }
+
     /// A hook running before inserting a model into the table.
     #[inline]
     async fn before_insert(&mut self) -> Result<Self::Data, Error> {
         self.before_save().await
     }
 
+    /// A hook running before inserting many models into the table,
+    /// prior to the hook `before_insert` of each model.
+    ///
+    /// It can be used to resolve the conflicts among the models in the same batch.
+    #[inline]
+    async fn before_insert_many(_models: &mut [Self]) -> Result<(), Error> {
+        Ok(())
+    }
+
     /// A hook running after inserting a model into the table.
     #[inline]
     async fn after_insert(ctx: &QueryContext, data: Self::Data) -> Result<(), Error> {
@@ -160,6 +176,21 @@ pub trait ModelHooks: Model {
         Ok(())
     }
 
+    /// A hook running before restoring a logically deleted model in the table.
+    #[inline]
+    async fn before_restore(&mut self) -> Result<Self::Data, Error> {
+        self.before_save().await
+    }
+
+    /// A hook running after restoring a logically deleted model in the table.
+    #[inline]
+    async fn after_restore(ctx: &QueryContext, data: Self::Data) -> Result<(), Error> {
+        Self::after_save(ctx, data).await?;
+        #[cfg(feature = "metrics")]
+        ctx.emit_metrics("restore");
+        Ok(())
+    }
+
     /// A hook running before updating a model in the table.
     #[inline]
     async fn before_update(&mut self) -> Result<Self::Data, Error> {
@@ -292,6 +323,18 @@ pub trait ModelHooks: Model {
         Ok(())
     }
 
+    /// A hook running after reading the models for a HTTP response,
+    /// which can be used to record the access in an audit log.
+    ///
+    /// It is called once for a single fetch or a list query with all the models.
+    #[inline]
+    async fn after_read(
+        _models: &[Map],
+        _extension: Option<&Self::Extension>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
     /// A hook running before returning the model data as a HTTP response.
     #[inline]
     async fn before_respond(
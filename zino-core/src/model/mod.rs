@@ -1,5 +1,8 @@
 //! Domain specific models.
-use crate::{validation::Validation, AvroValue, JsonValue, Map, Record};
+use crate::{
+    extension::TomlTableExt, state::State, validation::Validation, AvroValue, JsonValue, LazyLock,
+    Map, Record,
+};
 use serde::{de::DeserializeOwned, Serialize};
 
 mod column;
@@ -44,6 +47,15 @@ pub trait Model: Default + Serialize + DeserializeOwned {
         Self::MODEL_NAME
     }
 
+    /// Returns `true` if the unknown fields should be rejected in [`read_map()`](Model::read_map).
+    ///
+    /// The default value is determined by the global config `model.strict-mode`,
+    /// which is `false` for backward compatibility.
+    #[inline]
+    fn strict_mode() -> bool {
+        *STRICT_MODE
+    }
+
     /// Updates the model using the json object and returns the validation result.
     #[must_use]
     fn read_map(&mut self, data: &Map) -> Validation {
@@ -118,3 +130,12 @@ pub trait Model: Default + Serialize + DeserializeOwned {
         map
     }
 }
+
+/// Global strict mode for reading the model data.
+static STRICT_MODE: LazyLock<bool> = LazyLock::new(|| {
+    State::shared()
+        .config()
+        .get_table("model")
+        .and_then(|config| config.get_bool("strict-mode"))
+        .unwrap_or_default()
+});
@@ -11,6 +11,8 @@ pub struct Mutation {
     fields: Vec<String>,
     // Updates.
     updates: Map,
+    // Updates of the read-only fields.
+    read_only_updates: Map,
 }
 
 impl Mutation {
@@ -20,6 +22,7 @@ impl Mutation {
         Self {
             fields: Vec::new(),
             updates: updates.into().into_map_opt().unwrap_or_default(),
+            read_only_updates: Map::new(),
         }
     }
 
@@ -81,6 +84,16 @@ impl Mutation {
         self.updates.upsert(key, value);
     }
 
+    /// Adds a key-value pair to the updates of the read-only fields.
+    ///
+    /// Unlike [`add_update()`](Self::add_update), the field is written even if it is read-only,
+    /// and it is not restricted by the editable fields. It can never be set by the user input,
+    /// and is used for the fields maintained by the model itself, such as `deleted_at`.
+    #[inline]
+    pub fn add_read_only_update(&mut self, key: impl Into<String>, value: impl Into<JsonValue>) {
+        self.read_only_updates.upsert(key, value);
+    }
+
     /// Moves all elements from the `updates` into `self`.
     #[inline]
     pub fn append_updates(&mut self, updates: &mut Map) {
@@ -98,4 +111,10 @@ impl Mutation {
     pub fn updates(&self) -> &Map {
         &self.updates
     }
+
+    /// Returns a reference to the updates of the read-only fields.
+    #[inline]
+    pub fn read_only_updates(&self) -> &Map {
+        &self.read_only_updates
+    }
 }
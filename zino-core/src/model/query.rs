@@ -106,6 +106,10 @@ impl Query {
                         }
                     }
                 }
+                "no_cache" => {
+                    let flag = value.as_str() != Some("false");
+                    extra.upsert(key, flag);
+                }
                 "timestamp" | "nonce" | "signature" => {
                     extra.upsert(key, value.clone());
                 }
@@ -135,6 +139,19 @@ impl Query {
         if let Some(current_page) = pagination_current_page {
             self.offset = self.limit * current_page.saturating_sub(1);
         }
+        if self.show_deleted() {
+            // Removes the default filters which exclude the logically deleted models
+            let filters = &mut self.filters;
+            if filters
+                .get_object("status")
+                .is_some_and(|filter| filter.get_str("$ne") == Some("Deleted"))
+            {
+                filters.remove("status");
+            }
+            if filters.get_str("deleted_at") == Some("null") {
+                filters.remove("deleted_at");
+            }
+        }
         validation
     }
 
@@ -179,6 +196,16 @@ impl Query {
             .retain(|field| !fields.contains(&field.as_str()))
     }
 
+    /// Adds a projection field if it does not exist.
+    /// It has no effect if the projection fields are empty, which selects all the fields.
+    #[inline]
+    pub fn add_field(&mut self, field: impl Into<String>) {
+        let field = field.into();
+        if !self.fields.is_empty() && !self.fields.contains(&field) {
+            self.fields.push(field);
+        }
+    }
+
     /// Adds a projection field with the alias.
     #[inline]
     pub fn add_field_alias(&mut self, expr: impl Into<String>, alias: impl Into<String>) {
@@ -323,6 +350,12 @@ impl Query {
     pub fn no_check(&self) -> bool {
         self.enabled("no_check")
     }
+
+    /// Returns `true` if the `no_cache` flag has been enabled.
+    #[inline]
+    pub fn no_cache(&self) -> bool {
+        self.enabled("no_cache")
+    }
 }
 
 impl Default for Query {
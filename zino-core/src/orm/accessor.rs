@@ -1,14 +1,17 @@
-use super::{ModelHelper, Schema};
+use super::{
+    query::QueryExt, BulkOutcome, DatabaseDriver, ModelHelper, OptimisticLockConflict, QueryCache,
+    Schema,
+};
 use crate::{
     bail,
     datetime::DateTime,
     error::Error,
     extension::{JsonObjectExt, JsonValueExt},
-    model::{ModelHooks, Mutation, Query},
+    model::{Column, ModelHooks, Mutation, Query},
     validation::Validation,
     warn, JsonValue, Map,
 };
-use std::fmt::Display;
+use std::{fmt::Display, time::Duration};
 
 /// Access model fields.
 ///
@@ -203,6 +206,12 @@ where
         self.version() + 1
     }
 
+    /// Returns the context of an optimistic lock conflict for the current version.
+    #[inline]
+    fn version_conflict(&self) -> OptimisticLockConflict {
+        OptimisticLockConflict::new(Self::MODEL_NAME, self.id().to_string(), self.version())
+    }
+
     /// Constructs the query filters for the model of the current version.
     fn current_version_filters(&self) -> Map {
         let mut filters = Map::new();
@@ -227,10 +236,14 @@ where
     }
 
     /// Constructs the mutation updates for the model of the next version.
+    /// The `edition` field is bumped as well if it exists.
     fn next_version_updates(&self) -> Map {
         let mut updates = Map::new();
         updates.upsert("updated_at", DateTime::now().to_utc_timestamp());
         updates.upsert("version", self.next_version());
+        if Self::has_column("edition") {
+            updates.upsert("edition", self.next_edition());
+        }
         updates
     }
 
@@ -289,11 +302,15 @@ where
     }
 
     /// Constructs a `Mutation` for logically deleting the model.
+    /// The `deleted_at` field is stamped if it exists.
     fn soft_delete_mutation(&self) -> Mutation {
         let mut mutation = Self::default_mutation();
         let mut updates = self.next_edition_updates();
         updates.upsert("status", "Deleted");
         mutation.append_updates(&mut updates);
+        if Self::has_column("deleted_at") {
+            mutation.add_read_only_update("deleted_at", DateTime::now().to_utc_timestamp());
+        }
         mutation
     }
 
@@ -315,6 +332,19 @@ where
         mutation
     }
 
+    /// Constructs a `Mutation` for restoring the logically deleted model.
+    /// The `deleted_at` field is cleared if it exists.
+    fn restore_mutation(&self) -> Mutation {
+        let mut mutation = Self::default_mutation();
+        let mut updates = self.next_edition_updates();
+        updates.upsert("status", "Active");
+        mutation.append_updates(&mut updates);
+        if Self::has_column("deleted_at") {
+            mutation.add_read_only_update("deleted_at", JsonValue::Null);
+        }
+        mutation
+    }
+
     /// Constructs a default snapshot `Query` for the model.
     fn default_snapshot_query() -> Query {
         let mut query = Query::default();
@@ -341,6 +371,51 @@ where
         query
     }
 
+    /// Constructs a full-text search `Query` for the model,
+    /// ordered by the relevance if it is supported by the database.
+    ///
+    /// Only the columns with the `text` index type participate in the search,
+    /// and they can be restricted further by the `fields` if it is not empty.
+    /// For MySQL, the search is always performed over all the columns of the FULLTEXT index,
+    /// since `MATCH` requires the exact column list of the index.
+    /// The search terms are escaped, so it is safe to pass the user input directly.
+    /// No text search filter is added if there is no matched column or the terms are blank.
+    fn text_search_query(search: &str, fields: &[&str]) -> Query {
+        let mut query = Query::default();
+        let ignored_fields = [Self::write_only_fields(), &["extra"]].concat();
+        query.allow_fields(Self::fields());
+        query.deny_fields(&ignored_fields);
+        query.add_filter("status", Map::from_entry("$ne", "Deleted"));
+
+        let search = search.trim();
+        let full_index = cfg!(any(
+            feature = "orm-mariadb",
+            feature = "orm-mysql",
+            feature = "orm-tidb"
+        ));
+        let text_search_columns = text_search_columns(Self::columns(), fields, full_index);
+        if let Some(col) = text_search_columns.first().filter(|_| !search.is_empty()) {
+            let language = col
+                .index_type()
+                .and_then(|index_type| index_type.strip_prefix("text:"))
+                .unwrap_or("english");
+            let text_search_fields = text_search_columns
+                .iter()
+                .map(|col| col.name())
+                .collect::<Vec<_>>();
+            let mut filter = Map::new();
+            filter.upsert("$fields", text_search_fields);
+            filter.upsert("$search", search);
+            filter.upsert("$language", language);
+            if let Some(rank) = <Query as QueryExt<DatabaseDriver>>::format_text_rank(&filter) {
+                query.order_desc(rank);
+            }
+            query.add_filter("$text", filter);
+        }
+        query.order_desc("updated_at");
+        query
+    }
+
     /// Checks the constraints for the model.
     async fn check_constraints(&self) -> Result<Validation, Error> {
         let mut validation = Validation::new();
@@ -371,6 +446,120 @@ where
         Ok(model)
     }
 
+    /// Returns the TTL for caching the query results of the model,
+    /// or `None` if the cache is disabled.
+    ///
+    /// It is enabled for the models listed in `database.cache.models`.
+    /// If this method is overridden, the model should also be registered via
+    /// [`QueryCache::enable()`] so that the cache can be invalidated by the writes.
+    #[inline]
+    fn cache_ttl() -> Option<Duration> {
+        QueryCache::ttl(Self::model_name())
+    }
+
+    /// Finds a model selected by the primary key with the query cache.
+    /// It bypasses the cache if `no_cache` is `true`.
+    ///
+    /// The cached data is invalidated by every write of the [`Schema`] methods
+    /// regardless of the hooks, while the staleness of the writes bypassing them
+    /// is bounded by [`cache_ttl()`].
+    ///
+    /// [`cache_ttl()`]: ModelAccessor::cache_ttl
+    async fn find_cached_by_id(id: &K, no_cache: bool) -> Result<Option<Map>, Error> {
+        let ttl = Self::cache_ttl().filter(|_| !no_cache);
+        let Some(ttl) = ttl else {
+            return Self::find_by_id::<Map>(id).await;
+        };
+        let namespace = Self::model_name();
+        let key = format!("id:{id}");
+        if let Some(value) = QueryCache::get(namespace, &key).await {
+            return Ok(value.into_map_opt());
+        }
+
+        let model = Self::find_by_id::<Map>(id).await?;
+        let value = model.clone().map(JsonValue::Object).unwrap_or_default();
+        QueryCache::set(namespace, &key, value, ttl).await;
+        Ok(model)
+    }
+
+    /// Finds the models selected by the `Query` with the query cache.
+    ///
+    /// It bypasses the cache if the `no_cache` flag has been enabled for the query.
+    async fn find_cached(query: &Query) -> Result<Vec<Map>, Error> {
+        let ttl = Self::cache_ttl().filter(|_| !query.no_cache());
+        let Some(ttl) = ttl else {
+            return Self::find::<Map>(query).await;
+        };
+        let namespace = Self::model_name();
+        let key = QueryCache::query_key(query);
+        if let Some(JsonValue::Array(values)) = QueryCache::get(namespace, &key).await {
+            let models = values
+                .into_iter()
+                .filter_map(|value| value.into_map_opt())
+                .collect();
+            return Ok(models);
+        }
+
+        let models = Self::find::<Map>(query).await?;
+        let value = models
+            .iter()
+            .cloned()
+            .map(JsonValue::Object)
+            .collect::<Vec<_>>();
+        QueryCache::set(namespace, &key, value.into(), ttl).await;
+        Ok(models)
+    }
+
+    /// Finds the models whose point field is within the radius in meters of the point `(lng, lat)`,
+    /// ordered by the distance from near to far.
+    ///
+    /// The candidates are narrowed down by a bounding box in the database,
+    /// and the results are checked with the haversine formula.
+    /// The query is scoped by [`before_list()`](ModelHooks::before_list) with the extension.
+    async fn near(
+        point: (f64, f64),
+        radius: f64,
+        extension: Option<&<Self as ModelHooks>::Extension>,
+    ) -> Result<Vec<Map>, Error> {
+        let Some(col) = Self::columns().iter().find(|col| col.is_point_type()) else {
+            bail!(
+                "the model `{}` does not have a point field",
+                Self::model_name()
+            );
+        };
+        let field = col.name();
+        let (lng, lat) = point;
+        let mut query = Query::default();
+        let ignored_fields = [Self::write_only_fields(), &["extra"]].concat();
+        query.allow_fields(Self::fields());
+        query.deny_fields(&ignored_fields);
+        query.add_filter("status", Map::from_entry("$ne", "Deleted"));
+        let filter: Vec<JsonValue> = vec![field.into(), lng.into(), lat.into(), radius.into()];
+        query.add_filter("$near", filter);
+        if let Some(distance) =
+            <Query as QueryExt<DatabaseDriver>>::format_point_distance(field, point)
+        {
+            query.order_asc(distance);
+        }
+        Self::before_list(&mut query, extension).await?;
+
+        let models = Self::find::<Map>(&query).await?;
+        let mut models = models
+            .into_iter()
+            .filter_map(|model| {
+                let location = model.get_array(field)?;
+                let (Some(x), Some(y)) = (location.first()?.as_f64(), location.get(1)?.as_f64())
+                else {
+                    return None;
+                };
+                let distance = haversine_distance(point, (x, y));
+                (distance <= radius).then_some((distance, model))
+            })
+            .collect::<Vec<_>>();
+        models.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(models.into_iter().map(|(_, model)| model).collect())
+    }
+
     /// Deletes a model of the primary key by setting the status as `Deleted`.
     async fn soft_delete_by_id(id: &K) -> Result<(), Error> {
         let mut model = Self::try_get_model(id).await?;
@@ -379,6 +568,7 @@ where
         let query = model.current_version_query();
         let mut mutation = model.soft_delete_mutation();
         let ctx = Self::update_one(&query, &mut mutation).await?;
+        model.version_conflict().check(&ctx)?;
         Self::after_soft_delete(&ctx, model_data).await?;
         Ok(())
     }
@@ -391,6 +581,7 @@ where
         let query = model.current_version_query();
         let mut mutation = model.lock_mutation();
         let ctx = Self::update_one(&query, &mut mutation).await?;
+        model.version_conflict().check(&ctx)?;
         Self::after_lock(&ctx, model_data).await?;
         Ok(())
     }
@@ -403,33 +594,68 @@ where
         let query = model.current_version_query();
         let mut mutation = model.archive_mutation();
         let ctx = Self::update_one(&query, &mut mutation).await?;
+        model.version_conflict().check(&ctx)?;
         Self::after_archive(&ctx, model_data).await?;
         Ok(())
     }
 
+    /// Restores a logically deleted model of the primary key by setting the status as `Active`
+    /// and clearing the `deleted_at` field if it exists.
+    async fn restore_by_id(id: &K) -> Result<(), Error> {
+        let mut model = Self::try_get_model(id).await?;
+        let model_data = model.before_restore().await?;
+
+        let query = model.current_version_query();
+        let mut mutation = model.restore_mutation();
+        let ctx = Self::update_one(&query, &mut mutation).await?;
+        model.version_conflict().check(&ctx)?;
+        Self::after_restore(&ctx, model_data).await?;
+        Ok(())
+    }
+
     /// Updates a model of the primary key using the json object.
+    ///
+    /// See [`update_by_id_if_modified()`](Self::update_by_id_if_modified) for the details.
+    #[inline]
     async fn update_by_id(
         id: &K,
         data: &mut Map,
         extension: Option<<Self as ModelHooks>::Extension>,
     ) -> Result<(Validation, Self), Error> {
+        let (validation, model, _) = Self::update_by_id_if_modified(id, data, extension).await?;
+        Ok((validation, model))
+    }
+
+    /// Updates a model of the primary key using the json object, and returns
+    /// an additional flag indicating whether the model has been modified.
+    ///
+    /// # Note
+    ///
+    /// The incoming changes are compared against the current values, with the strings
+    /// compared exactly and the numbers compared with a tolerance for floating-point errors.
+    /// If there is no effective change, the update is skipped and the `updated_at`
+    /// and `version` fields are not bumped.
+    async fn update_by_id_if_modified(
+        id: &K,
+        data: &mut Map,
+        extension: Option<<Self as ModelHooks>::Extension>,
+    ) -> Result<(Validation, Self, bool), Error> {
         Self::before_extract().await?;
 
         let mut model = Self::try_get_model(id).await?;
+        model.before_access(extension.as_ref()).await?;
         if data
             .get_u64("version")
             .is_some_and(|version| model.version() != version)
         {
-            bail!(
-                "409 Conflict: there is a version conflict for the model `{}`",
-                id
-            );
+            return Err(model.version_conflict().into_error());
         }
         Self::before_validation(data, extension.as_ref()).await?;
 
+        let original_data = serde_json::to_value(&model)?;
         let validation = model.read_map(data);
         if !validation.is_success() {
-            return Ok((validation, model));
+            return Ok((validation, model, false));
         }
         if let Some(extension) = extension {
             model.after_extract(extension).await?;
@@ -437,7 +663,7 @@ where
 
         let validation = model.check_constraints().await?;
         if !validation.is_success() {
-            return Ok((validation, model));
+            return Ok((validation, model, false));
         }
         if model.is_deleted() {
             data.retain(|key, _value| key == "status");
@@ -448,13 +674,131 @@ where
         }
         model.after_validation(data).await?;
 
+        let read_only_fields = Self::read_only_fields();
+        let modified = has_changes(&original_data, data, |key| {
+            matches!(key, "updated_at" | "version")
+                || read_only_fields.contains(&key)
+                || !Self::has_column(key)
+        });
+        if !modified {
+            return Ok((validation, model, false));
+        }
+
         let query = model.current_version_query();
         let mut mutation = model.next_version_mutation(data);
 
         let model_data = model.before_update().await?;
         let ctx = Self::update_one(&query, &mut mutation).await?;
+        model.version_conflict().check(&ctx)?;
         Self::after_update(&ctx, model_data).await?;
-        Ok((validation, model))
+        Ok((validation, model, true))
+    }
+
+    /// Updates the models selected by the query using the changes in one statement,
+    /// and returns the number of rows affected.
+    ///
+    /// # Note
+    ///
+    /// The `updated_at` and `version` fields are bumped automatically.
+    /// Per-model hooks such as [`before_update()`](ModelHooks::before_update) are not run.
+    /// Instead, [`before_mutation()`](ModelHooks::before_mutation) and
+    /// [`after_mutation()`](ModelHooks::after_mutation) are run once for the whole change set.
+    /// The query is scoped by [`before_list()`](ModelHooks::before_list) with the extension.
+    async fn update_where(
+        query: &Query,
+        mut changes: Map,
+        extension: Option<&<Self as ModelHooks>::Extension>,
+    ) -> Result<u64, Error> {
+        let read_only_fields = Self::read_only_fields();
+        for (key, value) in changes.iter() {
+            let fields = if key.starts_with('$') {
+                value
+                    .as_object()
+                    .map(|update| update.keys().map(|key| key.as_str()).collect())
+                    .unwrap_or_default()
+            } else {
+                vec![key.as_str()]
+            };
+            for field in fields {
+                if read_only_fields.contains(&field) {
+                    bail!(
+                        "403 Forbidden: the read-only field `{}` can not be modified",
+                        field
+                    );
+                } else if !Self::has_column(field) {
+                    bail!("400 Bad Request: the field `{}` does not exist", field);
+                }
+            }
+        }
+        if changes.is_empty() {
+            bail!("400 Bad Request: the changes should be nonempty");
+        }
+        if Self::has_column("updated_at") {
+            changes.upsert("updated_at", DateTime::now().to_utc_timestamp());
+        }
+        if Self::has_column("version") {
+            if let Some(JsonValue::Object(increments)) = changes.get_mut("$inc") {
+                increments.upsert("version", 1);
+            } else {
+                changes.upsert("$inc", Map::from_entry("version", 1));
+            }
+        }
+
+        let mut query = query.clone();
+        Self::before_list(&mut query, extension).await?;
+
+        let mut mutation = Self::default_mutation();
+        mutation.append_updates(&mut changes);
+        let ctx = Self::update_many(&query, &mut mutation).await?;
+        Ok(ctx.rows_affected().unwrap_or_default())
+    }
+
+    /// Inserts many models constructed from the json objects in a single statement
+    /// inside of a transaction.
+    ///
+    /// Each object runs through the hooks `before_validation`, `after_validation`
+    /// and `after_extract` with the extension in the same way as a single insertion.
+    /// The models failed to pass the validation are skipped and recorded
+    /// in the outcome with their indexes, instead of aborting the whole batch.
+    async fn bulk_insert(
+        data: Vec<Map>,
+        extension: Option<<Self as ModelHooks>::Extension>,
+    ) -> Result<BulkOutcome, Error> {
+        let (models, mut outcome) = extract_models::<Self, K>(data, extension).await?;
+        if !models.is_empty() {
+            let ctx = Self::prepare_insert_many(models).await?;
+            outcome.execute::<Self>(ctx).await?;
+        }
+        Ok(outcome)
+    }
+
+    /// Updates or inserts many models constructed from the json objects
+    /// in a single statement inside of a transaction. The conflicts are resolved
+    /// by the primary key.
+    ///
+    /// See [`bulk_insert()`](Self::bulk_insert) for the details. The new rows run through
+    /// the hook `before_insert`, and the `version` of the existing rows is increased.
+    async fn bulk_upsert(
+        data: Vec<Map>,
+        extension: Option<<Self as ModelHooks>::Extension>,
+    ) -> Result<BulkOutcome, Error> {
+        let (models, mut outcome) = extract_models::<Self, K>(data, extension).await?;
+        if !models.is_empty() {
+            let ctx = Self::prepare_upsert_many(models).await?;
+            outcome.execute::<Self>(ctx).await?;
+        }
+        Ok(outcome)
+    }
+
+    /// Fetches the target model of the polymorphic reference for the field,
+    /// which is resolved by the type discriminator column.
+    /// It returns `None` if the field is not a polymorphic reference
+    /// or the target model does not exist.
+    ///
+    /// This method is generated by the `ModelAccessor` derive for the field
+    /// with the attribute `#[schema(reference_type = "..", reference_models = "..")]`.
+    async fn fetch_polymorphic_reference(&self, _field: &str) -> Result<Option<Map>, Error> {
+        Ok(None)
     }
 
     /// Generates random associations for the model.
@@ -506,3 +850,200 @@ where
         Ok((validation, model))
     }
 }
+
+/// Returns the columns with the `text` index type restricted by the `fields`.
+/// If `full_index` is `true`, all the indexed columns are returned as long as
+/// any of them is matched by the `fields`.
+fn text_search_columns<'a>(
+    columns: &'a [Column<'static>],
+    fields: &[&str],
+    full_index: bool,
+) -> Vec<&'a Column<'static>> {
+    let indexed_columns = columns
+        .iter()
+        .filter(|col| {
+            col.index_type()
+                .is_some_and(|index_type| index_type.starts_with("text"))
+        })
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        indexed_columns
+    } else if full_index {
+        if indexed_columns
+            .iter()
+            .any(|col| fields.contains(&col.name()))
+        {
+            indexed_columns
+        } else {
+            Vec::new()
+        }
+    } else {
+        indexed_columns
+            .into_iter()
+            .filter(|col| fields.contains(&col.name()))
+            .collect()
+    }
+}
+
+/// Returns `true` if any incoming value differs from the original data.
+/// The value is compared as it will be written by the mutation,
+/// so that the fields not parsed by `read_map` are also detected.
+fn has_changes(original_data: &JsonValue, data: &Map, skipped: impl Fn(&str) -> bool) -> bool {
+    data.iter().any(|(key, value)| {
+        if key.starts_with('$') {
+            true
+        } else if skipped(key) {
+            false
+        } else {
+            !eq_normalized(original_data.get(key), Some(value))
+        }
+    })
+}
+
+/// Returns `true` if the two JSON values are equal after normalization.
+fn eq_normalized(a: Option<&JsonValue>, b: Option<&JsonValue>) -> bool {
+    match (a, b) {
+        (Some(JsonValue::String(a)), Some(JsonValue::String(b))) => a == b,
+        (Some(JsonValue::Number(a)), Some(JsonValue::Number(b))) => {
+            if let (Some(a), Some(b)) = (a.as_f64(), b.as_f64()) {
+                let tolerance = f64::EPSILON * a.abs().max(b.abs()).max(1.0);
+                (a - b).abs() <= tolerance
+            } else {
+                a == b
+            }
+        }
+        (Some(JsonValue::Array(a)), Some(JsonValue::Array(b))) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| eq_normalized(Some(a), Some(b)))
+        }
+        (Some(JsonValue::Object(a)), Some(JsonValue::Object(b))) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, value)| eq_normalized(Some(value), b.get(key)))
+        }
+        (Some(JsonValue::Number(a)), Some(JsonValue::String(b)))
+        | (Some(JsonValue::String(b)), Some(JsonValue::Number(a))) => {
+            b.parse::<f64>().ok() == a.as_f64()
+        }
+        (None | Some(JsonValue::Null), None | Some(JsonValue::Null)) => true,
+        (a, b) => a == b,
+    }
+}
+
+/// Extracts the models from the json objects, and records the validation failures.
+async fn extract_models<M, K>(
+    data: Vec<Map>,
+    extension: Option<M::Extension>,
+) -> Result<(Vec<M>, BulkOutcome), Error>
+where
+    M: ModelAccessor<K>,
+    K: Default + Display + PartialEq,
+{
+    let mut models = Vec::with_capacity(data.len());
+    let mut outcome = BulkOutcome::default();
+    for (index, mut map) in data.into_iter().enumerate() {
+        M::before_extract().await?;
+        M::before_validation(&mut map, extension.as_ref()).await?;
+
+        let mut model = M::new();
+        let mut validation = model.read_map(&map);
+        if validation.is_success() {
+            model.before_insert_check(extension.as_ref()).await?;
+            validation = model.check_constraints().await?;
+        }
+        if validation.is_success() {
+            model.after_validation(&mut map).await?;
+            if let Some(ref extension) = extension {
+                model.after_extract(extension.clone()).await?;
+            }
+            models.push(model);
+        } else {
+            outcome.record_failure(index, validation);
+        }
+    }
+    Ok((models, outcome))
+}
+
+/// Returns the great-circle distance in meters between two points `(lng, lat)`.
+fn haversine_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lng1, lat1) = (a.0.to_radians(), a.1.to_radians());
+    let (lng2, lat2) = (b.0.to_radians(), b.1.to_radians());
+    let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lng2 - lng1) / 2.0).sin().powi(2);
+    2.0 * super::query::EARTH_RADIUS * h.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_changes, haversine_distance, text_search_columns};
+    use crate::{extension::JsonObjectExt, model::Column};
+
+    #[test]
+    fn it_detects_effective_changes() {
+        let original_data = serde_json::json!({
+            "name": "zino",
+            "status": "Deleted",
+            "version": 3,
+            "score": 0.3,
+        });
+        let skipped = |key: &str| key == "version";
+
+        let mut data = crate::Map::new();
+        data.upsert("status", "Active");
+        assert!(has_changes(&original_data, &data, skipped));
+
+        let mut data = crate::Map::new();
+        data.upsert("status", "Deleted");
+        data.upsert("name", "zino");
+        data.upsert("score", 0.1 + 0.2);
+        data.upsert("version", 4);
+        assert!(!has_changes(&original_data, &data, skipped));
+
+        data.upsert("score", "0.3");
+        assert!(!has_changes(&original_data, &data, skipped));
+
+        data.upsert("name", " zino ");
+        assert!(has_changes(&original_data, &data, skipped));
+    }
+
+    #[test]
+    fn it_selects_text_search_columns() {
+        let mut name = Column::new("name", "String", true);
+        name.set_index_type("text");
+        let mut description = Column::new("description", "String", false);
+        description.set_index_type("text");
+        let status = Column::new("status", "String", true);
+        let columns = [name, description, status];
+
+        fn names<'a>(columns: Vec<&Column<'a>>) -> Vec<&'a str> {
+            columns.iter().map(|col| col.name()).collect()
+        }
+        assert_eq!(
+            names(text_search_columns(&columns, &[], false)),
+            ["name", "description"]
+        );
+        assert_eq!(
+            names(text_search_columns(&columns, &["name"], false)),
+            ["name"]
+        );
+        assert_eq!(
+            names(text_search_columns(&columns, &["name"], true)),
+            ["name", "description"]
+        );
+        assert!(text_search_columns(&columns, &["status"], true).is_empty());
+    }
+
+    #[test]
+    fn it_computes_haversine_distance() {
+        let beijing = (116.4074, 39.9042);
+        let shanghai = (121.4737, 31.2304);
+        let distance = haversine_distance(beijing, shanghai);
+        assert!((distance - 1_067_000.0).abs() < 5_000.0);
+        assert_eq!(haversine_distance(beijing, beijing), 0.0);
+
+        let distance = haversine_distance((0.0, 0.0), (0.0, 1.0));
+        assert!((distance - 111_195.0).abs() < 10.0);
+    }
+}
@@ -0,0 +1,65 @@
+use super::{Executor, QueryCache, Schema};
+use crate::{error::Error, model::QueryContext, validation::Validation};
+use sqlx::Acquire;
+
+/// The outcome of a bulk insertion or upsertion.
+///
+/// The models failed to pass the validation are skipped, and their validations
+/// are collected with the indexes in the input data.
+#[derive(Debug, Default)]
+pub struct BulkOutcome {
+    /// Number of rows affected.
+    rows_affected: u64,
+    /// Validations of the failed models with the indexes.
+    failures: Vec<(usize, Validation)>,
+}
+
+impl BulkOutcome {
+    /// Returns the number of rows affected.
+    #[inline]
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// Returns the validations of the failed models with the indexes in the input data.
+    #[inline]
+    pub fn failures(&self) -> &[(usize, Validation)] {
+        &self.failures
+    }
+
+    /// Consumes `self` and returns the validations of the failed models.
+    #[inline]
+    pub fn into_failures(self) -> Vec<(usize, Validation)> {
+        self.failures
+    }
+
+    /// Returns `true` if all the models pass the validation.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Records a validation failure for the model of the index.
+    #[inline]
+    pub(super) fn record_failure(&mut self, index: usize, validation: Validation) {
+        self.failures.push((index, validation));
+    }
+
+    /// Executes the query of a bulk operation inside of a transaction,
+    /// and records the number of rows affected.
+    pub(super) async fn execute<M: Schema>(&mut self, mut ctx: QueryContext) -> Result<(), Error> {
+        if ctx.is_cancelled() {
+            return Ok(());
+        }
+
+        let mut transaction = M::acquire_writer().await?.pool().begin().await?;
+        let connection = transaction.acquire().await?;
+        let rows_affected = connection.execute(ctx.query()).await?.rows_affected();
+        ctx.set_query_result(rows_affected, true);
+        M::after_scan(&ctx).await?;
+        transaction.commit().await?;
+        QueryCache::invalidate(M::model_name()).await;
+        self.rows_affected = rows_affected;
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+use crate::{
+    error::Error, extension::TomlTableExt, model::Query, state::State, BoxFuture, JsonValue,
+    LazyLock,
+};
+use lru::LruCache;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::HashSet,
+    num::NonZeroUsize,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+/// A pluggable backend for caching the query results.
+pub trait CacheBackend: Send + Sync {
+    /// Gets the cached value of the key in the namespace.
+    fn get<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<JsonValue>, Error>>;
+
+    /// Caches the value of the key in the namespace with the TTL.
+    fn set<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+        value: JsonValue,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<(), Error>>;
+
+    /// Invalidates all the cached values in the namespace.
+    fn invalidate<'a>(&'a self, namespace: &'a str) -> BoxFuture<'a, Result<(), Error>>;
+}
+
+/// An in-memory LRU cache, where the lookups and insertions take `O(1)` time.
+#[derive(Debug)]
+pub struct MemoryCache {
+    /// Cached values with the expiration time.
+    entries: Mutex<LruCache<String, (JsonValue, Instant)>>,
+}
+
+impl MemoryCache {
+    /// Creates a new instance with the capacity.
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    fn get<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<JsonValue>, Error>> {
+        let key = format!("{namespace}:{key}");
+        let mut entries = self.entries.lock();
+        let value = match entries.get(&key) {
+            Some((value, expires_at)) if *expires_at > Instant::now() => Some(value.clone()),
+            Some(_) => {
+                entries.pop(&key);
+                None
+            }
+            None => None,
+        };
+        Box::pin(async move { Ok(value) })
+    }
+
+    fn set<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+        value: JsonValue,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        let key = format!("{namespace}:{key}");
+        let expires_at = Instant::now() + ttl;
+        self.entries.lock().put(key, (value, expires_at));
+        Box::pin(async { Ok(()) })
+    }
+
+    fn invalidate<'a>(&'a self, namespace: &'a str) -> BoxFuture<'a, Result<(), Error>> {
+        let prefix = format!("{namespace}:");
+        let mut entries = self.entries.lock();
+        let keys = entries
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in keys {
+            entries.pop(&key);
+        }
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// A cache backed by a storage accessor, such as Redis.
+///
+/// The namespace is invalidated by bumping a generation marker,
+/// so that it works for the services without the listing capability.
+#[cfg(feature = "accessor")]
+#[derive(Debug, Clone)]
+pub struct AccessorCache {
+    /// Storage operator.
+    operator: opendal::Operator,
+}
+
+#[cfg(feature = "accessor")]
+impl AccessorCache {
+    /// Creates a new instance with the operator.
+    #[inline]
+    pub fn new(operator: opendal::Operator) -> Self {
+        Self { operator }
+    }
+
+    /// Reads the raw value of the path.
+    async fn read_value(&self, path: &str) -> Result<Option<JsonValue>, Error> {
+        match self.operator.read(path).await {
+            Ok(buffer) => Ok(Some(serde_json::from_slice(&buffer.to_vec())?)),
+            Err(err) if err.kind() == opendal::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Returns the path of the key in the current generation of the namespace.
+    async fn entry_path(&self, namespace: &str, key: &str) -> Result<String, Error> {
+        let generation_path = format!("{namespace}/generation");
+        let generation = self
+            .read_value(&generation_path)
+            .await?
+            .and_then(|value| value.as_str().map(|s| s.to_owned()))
+            .unwrap_or_else(|| "0".to_owned());
+        Ok(format!("{namespace}/{generation}/{key}"))
+    }
+}
+
+#[cfg(feature = "accessor")]
+impl CacheBackend for AccessorCache {
+    fn get<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<JsonValue>, Error>> {
+        Box::pin(async move {
+            let path = self.entry_path(namespace, key).await?;
+            let Some(mut entry) = self.read_value(&path).await? else {
+                return Ok(None);
+            };
+            let expires_at = entry.get("expires_at").and_then(|v| v.as_i64());
+            if expires_at.is_some_and(|t| t > crate::datetime::DateTime::now().timestamp_millis()) {
+                Ok(entry.get_mut("value").map(JsonValue::take))
+            } else {
+                Ok(None)
+            }
+        })
+    }
+
+    fn set<'a>(
+        &'a self,
+        namespace: &'a str,
+        key: &'a str,
+        value: JsonValue,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let path = self.entry_path(namespace, key).await?;
+            let ttl_millis = i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX);
+            let expires_at = crate::datetime::DateTime::now()
+                .timestamp_millis()
+                .saturating_add(ttl_millis);
+            let entry = serde_json::json!({
+                "expires_at": expires_at,
+                "value": value,
+            });
+            self.operator.write(&path, entry.to_string()).await?;
+            Ok(())
+        })
+    }
+
+    fn invalidate<'a>(&'a self, namespace: &'a str) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let generation_path = format!("{namespace}/generation");
+            let generation = JsonValue::from(crate::Uuid::now_v7().to_string());
+            self.operator
+                .write(&generation_path, generation.to_string())
+                .await?;
+            Ok(())
+        })
+    }
+}
+
+/// Global access to the query cache.
+///
+/// The cache is configured in the `database.cache` table:
+///
+/// ```toml
+/// [database.cache]
+/// capacity = 10000
+/// ttl = "1m"
+/// models = ["user", "tag"]
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryCache;
+
+impl QueryCache {
+    /// Sets the cache backend. It should be called before any cached query.
+    /// Returns `false` if the backend has already been set.
+    #[inline]
+    pub fn set_backend(backend: impl CacheBackend + 'static) -> bool {
+        CACHE_BACKEND.set(Box::new(backend)).is_ok()
+    }
+
+    /// Enables the cache for the namespace.
+    #[inline]
+    pub fn enable(namespace: &'static str) {
+        CACHED_NAMESPACES.write().insert(namespace);
+    }
+
+    /// Returns `true` if the cache has been enabled for the namespace.
+    #[inline]
+    pub fn is_enabled(namespace: &str) -> bool {
+        CACHED_NAMESPACES.read().contains(namespace)
+    }
+
+    /// Returns the default TTL for the namespace if the cache has been enabled.
+    #[inline]
+    pub fn ttl(namespace: &str) -> Option<Duration> {
+        Self::is_enabled(namespace).then_some(*DEFAULT_TTL)
+    }
+
+    /// Gets the cached value of the key in the namespace.
+    /// Failures of the backend are logged and treated as cache misses.
+    pub async fn get(namespace: &str, key: &str) -> Option<JsonValue> {
+        match backend().get(namespace, key).await {
+            Ok(value) => value,
+            Err(err) => {
+                tracing::warn!(namespace, key, "fail to read the query cache: {err}");
+                None
+            }
+        }
+    }
+
+    /// Caches the value of the key in the namespace with the TTL.
+    pub async fn set(namespace: &str, key: &str, value: JsonValue, ttl: Duration) {
+        if let Err(err) = backend().set(namespace, key, value, ttl).await {
+            tracing::warn!(namespace, key, "fail to write the query cache: {err}");
+        }
+    }
+
+    /// Invalidates the cached values in the namespace if the cache has been enabled.
+    pub async fn invalidate(namespace: &str) {
+        if Self::is_enabled(namespace) {
+            if let Err(err) = backend().invalidate(namespace).await {
+                tracing::warn!(namespace, "fail to invalidate the query cache: {err}");
+            }
+        }
+    }
+
+    /// Returns a cache key for the query.
+    pub fn query_key(query: &Query) -> String {
+        let sort_order = query
+            .sort_order()
+            .iter()
+            .map(|(field, descending)| format!("{field}:{descending}"))
+            .collect::<Vec<_>>();
+        let data = serde_json::json!({
+            "fields": query.fields(),
+            "filters": query.filters(),
+            "sort_order": sort_order,
+            "offset": query.offset(),
+            "limit": query.limit(),
+        });
+        let digest = crate::crypto::digest(data.to_string().as_bytes());
+        format!("query:{}", crate::encoding::hex::encode(digest))
+    }
+}
+
+/// Returns the cache backend.
+fn backend() -> &'static dyn CacheBackend {
+    CACHE_BACKEND
+        .get_or_init(|| {
+            let capacity = State::shared()
+                .get_config("database")
+                .and_then(|config| config.get_table("cache"))
+                .and_then(|config| config.get_usize("capacity"))
+                .unwrap_or(10000);
+            Box::new(MemoryCache::new(capacity))
+        })
+        .as_ref()
+}
+
+/// Cache backend.
+static CACHE_BACKEND: OnceLock<Box<dyn CacheBackend>> = OnceLock::new();
+
+/// Namespaces with the cache enabled.
+static CACHED_NAMESPACES: LazyLock<RwLock<HashSet<&'static str>>> = LazyLock::new(|| {
+    let namespaces = State::shared()
+        .get_config("database")
+        .and_then(|config| config.get_table("cache"))
+        .and_then(|config| config.get_str_array("models"))
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    RwLock::new(namespaces)
+});
+
+/// Default TTL of the cached values.
+static DEFAULT_TTL: LazyLock<Duration> = LazyLock::new(|| {
+    State::shared()
+        .get_config("database")
+        .and_then(|config| config.get_table("cache"))
+        .and_then(|config| config.get_duration("ttl"))
+        .unwrap_or_else(|| Duration::from_secs(60))
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheBackend, MemoryCache};
+    use futures::executor::block_on;
+    use std::time::Duration;
+
+    #[test]
+    fn it_evicts_least_recently_used_entries() {
+        let cache = MemoryCache::new(2);
+        let ttl = Duration::from_secs(60);
+        block_on(async {
+            cache.set("user", "a", 1.into(), ttl).await.unwrap();
+            cache.set("user", "b", 2.into(), ttl).await.unwrap();
+            assert_eq!(cache.get("user", "a").await.unwrap(), Some(1.into()));
+
+            cache.set("tag", "c", 3.into(), ttl).await.unwrap();
+            assert_eq!(cache.get("user", "b").await.unwrap(), None);
+            assert_eq!(cache.get("user", "a").await.unwrap(), Some(1.into()));
+
+            cache.invalidate("user").await.unwrap();
+            assert_eq!(cache.get("user", "a").await.unwrap(), None);
+            assert_eq!(cache.get("tag", "c").await.unwrap(), Some(3.into()));
+
+            cache
+                .set("tag", "d", 4.into(), Duration::ZERO)
+                .await
+                .unwrap();
+            assert_eq!(cache.get("tag", "d").await.unwrap(), None);
+        });
+    }
+}
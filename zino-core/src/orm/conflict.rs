@@ -0,0 +1,69 @@
+use crate::{error::Error, model::QueryContext};
+
+/// An error context for the optimistic lock conflict.
+///
+/// It is attached to the error returned when a compare-and-swap update on the `version`
+/// field affects no rows, and can be retrieved by
+/// [`Error::get_context::<OptimisticLockConflict>()`](Error::get_context)
+/// so that the caller can retry or respond with `409 Conflict`.
+#[derive(Debug, Clone)]
+pub struct OptimisticLockConflict {
+    /// Model name.
+    model_name: &'static str,
+    /// Primary key of the model.
+    primary_key: String,
+    /// Version expected by the update.
+    version: u64,
+}
+
+impl OptimisticLockConflict {
+    /// Creates a new instance.
+    #[inline]
+    pub(super) fn new(model_name: &'static str, primary_key: String, version: u64) -> Self {
+        Self {
+            model_name,
+            primary_key,
+            version,
+        }
+    }
+
+    /// Returns the model name.
+    #[inline]
+    pub fn model_name(&self) -> &'static str {
+        self.model_name
+    }
+
+    /// Returns the primary key of the model.
+    #[inline]
+    pub fn primary_key(&self) -> &str {
+        &self.primary_key
+    }
+
+    /// Returns the version expected by the update.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Converts `self` into an error with the context.
+    pub(super) fn into_error(self) -> Error {
+        let message = format!(
+            "409 Conflict: the version `{}` of the model `{}` is outdated",
+            self.version, self.primary_key
+        );
+        tracing::warn!(model_name = self.model_name, message);
+        let mut err = Error::new(message);
+        err.set_context(self);
+        err
+    }
+
+    /// Checks the query context of a compare-and-swap update,
+    /// and returns an error if no rows are affected.
+    pub fn check(self, ctx: &QueryContext) -> Result<(), Error> {
+        if ctx.is_cancelled() || ctx.rows_affected() != Some(0) {
+            Ok(())
+        } else {
+            Err(self.into_error())
+        }
+    }
+}
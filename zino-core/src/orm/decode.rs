@@ -163,6 +163,84 @@ where
     }
 }
 
+/// Decodes a single value as a geographic point `(lng, lat)` for the field in a row.
+#[cfg(feature = "orm-postgres")]
+pub fn decode_point(row: &DatabaseRow, field: &str) -> Result<Option<(f64, f64)>, Error> {
+    use sqlx::{postgres::PgValueFormat, ValueRef};
+
+    let value = row.try_get_raw(field)?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    match value.format() {
+        PgValueFormat::Binary => {
+            let bytes = value
+                .as_bytes()
+                .map_err(|err| Error::new(err.to_string()))?;
+            if let (Some(x), Some(y)) = (bytes.get(0..8), bytes.get(8..16)) {
+                let lng = f64::from_be_bytes(x.try_into()?);
+                let lat = f64::from_be_bytes(y.try_into()?);
+                return Ok(Some((lng, lat)));
+            }
+        }
+        PgValueFormat::Text => {
+            let text = value.as_str().map_err(|err| Error::new(err.to_string()))?;
+            let text = text.trim_start_matches('(').trim_end_matches(')');
+            if let Some((lng, lat)) = text.split_once(',') {
+                return Ok(Some((lng.trim().parse()?, lat.trim().parse()?)));
+            }
+        }
+    }
+    crate::bail!("invalid point data for the `{}` field", field);
+}
+
+/// Decodes a single value as a geographic point `(lng, lat)` for the field in a row.
+#[cfg(any(feature = "orm-mariadb", feature = "orm-mysql", feature = "orm-tidb"))]
+pub fn decode_point(row: &DatabaseRow, field: &str) -> Result<Option<(f64, f64)>, Error> {
+    use sqlx::ValueRef;
+
+    // The internal format is a 4-byte SRID followed by the WKB representation.
+    let value = row.try_get_raw(field)?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    let bytes = decode_raw::<Vec<u8>>(field, value)?;
+    if let (Some(&byte_order), Some(x), Some(y)) =
+        (bytes.get(4), bytes.get(9..17), bytes.get(17..25))
+    {
+        let (lng, lat) = if byte_order == 1 {
+            (
+                f64::from_le_bytes(x.try_into()?),
+                f64::from_le_bytes(y.try_into()?),
+            )
+        } else {
+            (
+                f64::from_be_bytes(x.try_into()?),
+                f64::from_be_bytes(y.try_into()?),
+            )
+        };
+        Ok(Some((lng, lat)))
+    } else {
+        crate::bail!("invalid point data for the `{}` field", field);
+    }
+}
+
+/// Decodes a single value as a geographic point `(lng, lat)` for the field in a row.
+#[cfg(not(any(
+    feature = "orm-mariadb",
+    feature = "orm-mysql",
+    feature = "orm-postgres",
+    feature = "orm-tidb"
+)))]
+pub fn decode_point(row: &DatabaseRow, field: &str) -> Result<Option<(f64, f64)>, Error> {
+    // The point is stored as a JSON array `[lng, lat]`.
+    let Some(value) = decode::<Option<String>>(row, field)? else {
+        return Ok(None);
+    };
+    let point = serde_json::from_str::<Option<(f64, f64)>>(&value)?;
+    Ok(point)
+}
+
 /// Decodes a raw value at the index.
 #[inline]
 pub(super) fn decode_raw<'r, T>(
@@ -1,12 +1,14 @@
 use super::Schema;
 use crate::{
-    crypto,
-    encoding::base64,
+    bail, crypto,
+    encoding::{base64, hex},
     error::Error,
     extension::{JsonObjectExt, TomlTableExt},
+    model::Query,
     state::State,
-    warn, LazyLock, Map,
+    warn, JsonValue, LazyLock, Map,
 };
+use hmac::{Hmac, Mac};
 use std::fmt::Display;
 
 /// Helper utilities for models.
@@ -52,6 +54,202 @@ where
         }
     }
 
+    /// Returns the paths of the encrypted fields with a flag indicating
+    /// whether the deterministic encryption is used or not.
+    ///
+    /// The encrypted fields can be declared by the column attribute `encrypted`
+    /// for the whole string value, or `encrypted = "field1, field2"` for the subfields
+    /// of a JSON object column. The column attribute `deterministic` enables
+    /// the deterministic encryption. The paths can also be configured by
+    /// `database.encryption.fields` and `database.encryption.deterministic-fields`
+    /// with the model name as the key.
+    fn encrypted_fields() -> Vec<(String, bool)> {
+        let mut fields = Vec::new();
+        for col in Self::columns() {
+            let name = col.name();
+            let deterministic = col.has_attribute("deterministic");
+            match col.extra().get("encrypted") {
+                Some(JsonValue::String(subfields)) => {
+                    for subfield in subfields.split(',') {
+                        let subfield = subfield.trim();
+                        if !subfield.is_empty() {
+                            fields.push(([name, ".", subfield].concat(), deterministic));
+                        }
+                    }
+                }
+                Some(_) => fields.push((name.to_owned(), deterministic)),
+                None => (),
+            }
+        }
+
+        let model_name = Self::model_name();
+        let app_config = State::shared().config();
+        let config = app_config
+            .get_table("database")
+            .and_then(|config| config.get_table("encryption"));
+        if let Some(config) = config {
+            for (key, deterministic) in [("fields", false), ("deterministic-fields", true)] {
+                let paths = config
+                    .get_table(key)
+                    .and_then(|config| config.get_str_array(model_name))
+                    .unwrap_or_default();
+                for path in paths {
+                    if !fields.iter().any(|(field, _)| field == path) {
+                        fields.push((path.to_owned(), deterministic));
+                    }
+                }
+            }
+        }
+        fields
+    }
+
+    /// Encrypts the value of an encrypted field with the current encryption key.
+    /// The ciphertext has the form `enc:{key_id}:{base64}`, so that it can be decrypted
+    /// by the original key after the key rotation.
+    fn encrypt_field_value(value: &str, deterministic: bool) -> Result<String, Error> {
+        let (key_id, key) = ENCRYPTION_KEYS
+            .first()
+            .ok_or_else(|| warn!("there is no encryption key"))?;
+        encrypt_value(value, key_id, key, deterministic)
+    }
+
+    /// Decrypts the value of an encrypted field.
+    /// The value without the `enc:` prefix is regarded as a plaintext.
+    #[inline]
+    fn decrypt_field_value(value: &str) -> Result<String, Error> {
+        decrypt_value(value, &ENCRYPTION_KEYS).unwrap_or_else(|| Ok(value.to_owned()))
+    }
+
+    /// Encrypts the string values of the encrypted fields for the model data.
+    ///
+    /// A value with the `enc:` prefix is left as it is only if it can be decrypted
+    /// by one of the keys, since such a ciphertext can not be forged without the key.
+    fn encrypt_fields(model: &mut Map) -> Result<(), Error> {
+        for (path, deterministic) in Self::encrypted_fields() {
+            if let Some(JsonValue::String(value)) = field_value_mut(model, &path) {
+                if !decrypt_value(value, &ENCRYPTION_KEYS).is_some_and(|result| result.is_ok()) {
+                    *value = Self::encrypt_field_value(value, deterministic)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decrypts the string values of the encrypted fields for the model data.
+    fn decrypt_fields(model: &mut Map) -> Result<(), Error> {
+        for (path, _) in Self::encrypted_fields() {
+            if let Some(JsonValue::String(value)) = field_value_mut(model, &path) {
+                *value = Self::decrypt_field_value(value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encrypts the filter values for the deterministic encrypted fields of the query.
+    /// It is an error to filter the fields encrypted by the nondeterministic encryption.
+    ///
+    /// The values are encrypted with every key, and the filter matches any of the ciphertexts,
+    /// so that the rows encrypted before the key rotation can still be selected.
+    fn encrypt_filters(query: &mut Query) -> Result<(), Error> {
+        let mut conditions = Vec::new();
+        for (path, deterministic) in Self::encrypted_fields() {
+            let Some(value) = query.remove_filter(&path) else {
+                continue;
+            };
+            if !deterministic {
+                bail!(
+                    "400 Bad Request: the encrypted field `{}` can not be filtered",
+                    path
+                );
+            }
+            let values = match value {
+                JsonValue::String(s) => vec![s],
+                JsonValue::Array(vec) => vec
+                    .into_iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_owned()))
+                    .collect(),
+                _ => bail!(
+                    "400 Bad Request: only the equality filters are supported for the encrypted field `{}`",
+                    path
+                ),
+            };
+            let alternatives = encrypt_filter_values(&path, &values, &ENCRYPTION_KEYS)?;
+            conditions.push(Map::from_entry("$or", alternatives).into());
+        }
+        if !conditions.is_empty() {
+            if let Some(JsonValue::Array(mut filters)) = query.remove_filter("$and") {
+                filters.append(&mut conditions);
+                conditions = filters;
+            }
+            query.add_filter("$and", conditions);
+        }
+        Ok(())
+    }
+
+    /// Computes the integrity digest of the fields for the model data,
+    /// which has the form `{algorithm}:{hex}`.
+    ///
+    /// The fields are serialized as a canonical JSON object in which the keys are sorted
+    /// recursively and there are no whitespaces, so that the digest is reproducible
+    /// across inserts and reads. A missing field is serialized as `null`.
+    fn integrity_digest(model: &Map, fields: &[&str]) -> String {
+        let mut fields = fields.to_vec();
+        fields.sort_unstable();
+        fields.dedup();
+
+        let mut data = String::from("{");
+        for (index, field) in fields.into_iter().enumerate() {
+            if index > 0 {
+                data.push(',');
+            }
+            data.push_str(&JsonValue::from(field).to_string());
+            data.push(':');
+            write_canonical_json(model.get(field).unwrap_or(&JsonValue::Null), &mut data);
+        }
+        data.push('}');
+
+        let algorithm = if cfg!(feature = "crypto-sm") {
+            "sm3"
+        } else {
+            "sha256"
+        };
+        let digest = crypto::digest(data.as_bytes());
+        format!("{algorithm}:{}", hex::encode(digest))
+    }
+
+    /// Returns the key for signing the integrity digest,
+    /// which is derived from `database.integrity.signing-key`.
+    #[inline]
+    fn integrity_signing_key() -> Option<&'static [u8]> {
+        INTEGRITY_SIGNING_KEY.as_ref().map(|key| key.as_slice())
+    }
+
+    /// Signs the integrity digest with the key, and returns the base64-encoded HMAC.
+    fn sign_integrity(integrity: &str, key: &[u8]) -> String {
+        let mut mac =
+            Hmac::<crypto::Digest>::new_from_slice(key).expect("HMAC can take key of any size");
+        mac.update(integrity.as_bytes());
+        base64::encode(mac.finalize().into_bytes())
+    }
+
+    /// Verifies the signature of the integrity digest with the key.
+    fn verify_integrity_signature(
+        integrity: &str,
+        signature: &str,
+        key: &[u8],
+    ) -> Result<(), Error> {
+        let signature = base64::decode(signature)?;
+        let mut mac =
+            Hmac::<crypto::Digest>::new_from_slice(key).expect("HMAC can take key of any size");
+        mac.update(integrity.as_bytes());
+        mac.verify_slice(&signature).map_err(|_| {
+            warn!(
+                "the integrity signature of `{}` is invalid",
+                Self::model_name()
+            )
+        })
+    }
+
     /// Translates the model data.
     fn translate_model(model: &mut Map) {
         #[cfg(feature = "openapi")]
@@ -74,6 +272,140 @@ where
 {
 }
 
+/// Encrypts the value with the key, and returns the ciphertext
+/// which has the form `enc:{key_id}:{base64}`.
+fn encrypt_value(
+    value: &str,
+    key_id: &str,
+    key: &[u8],
+    deterministic: bool,
+) -> Result<String, Error> {
+    let plaintext = value.as_bytes();
+    let ciphertext = if deterministic {
+        crypto::encrypt_deterministic(plaintext, key)
+    } else {
+        crypto::encrypt(plaintext, key)
+    }
+    .map_err(|err| warn!("fail to encrypt the field value: {}", err.message()))?;
+    Ok(format!("enc:{key_id}:{}", base64::encode(ciphertext)))
+}
+
+/// Decrypts the value which has the form `enc:{key_id}:{base64}` with the keys.
+/// It returns `None` if the value does not have the `enc:` prefix.
+fn decrypt_value(value: &str, keys: &[(String, [u8; 64])]) -> Option<Result<String, Error>> {
+    let (key_id, data) = value.strip_prefix("enc:")?.split_once(':')?;
+    let result = keys
+        .iter()
+        .find_map(|(id, key)| (id == key_id).then_some(key))
+        .ok_or_else(|| warn!("the encryption key `{}` does not exist", key_id))
+        .and_then(|key| {
+            let ciphertext = base64::decode(data)?;
+            let plaintext = crypto::decrypt(&ciphertext, key)
+                .map_err(|err| warn!("fail to decrypt the field value: {}", err.message()))?;
+            Ok(String::from_utf8(plaintext)?)
+        });
+    Some(result)
+}
+
+/// Encrypts the filter values deterministically with every key,
+/// and returns the alternative filters for the path.
+fn encrypt_filter_values(
+    path: &str,
+    values: &[String],
+    keys: &[(String, [u8; 64])],
+) -> Result<Vec<JsonValue>, Error> {
+    if keys.is_empty() {
+        bail!("there is no encryption key");
+    }
+    let mut alternatives = Vec::with_capacity(values.len() * keys.len());
+    for value in values {
+        for (key_id, key) in keys {
+            let ciphertext = encrypt_value(value, key_id, key, true)?;
+            alternatives.push(Map::from_entry(path, ciphertext).into());
+        }
+    }
+    Ok(alternatives)
+}
+
+/// Returns a mutable reference to the field value for the path `field` or `field.subfield`.
+fn field_value_mut<'a>(model: &'a mut Map, path: &str) -> Option<&'a mut JsonValue> {
+    if let Some((field, subfield)) = path.split_once('.') {
+        model.get_mut(field)?.as_object_mut()?.get_mut(subfield)
+    } else {
+        model.get_mut(path)
+    }
+}
+
+/// Writes the JSON value in the canonical form with the object keys sorted.
+fn write_canonical_json(value: &JsonValue, output: &mut String) {
+    match value {
+        JsonValue::Array(vec) => {
+            output.push('[');
+            for (index, value) in vec.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_canonical_json(value, output);
+            }
+            output.push(']');
+        }
+        JsonValue::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            output.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                output.push_str(&JsonValue::from(key.as_str()).to_string());
+                output.push(':');
+                write_canonical_json(value, output);
+            }
+            output.push('}');
+        }
+        _ => output.push_str(&value.to_string()),
+    }
+}
+
+/// Key for signing the integrity digests.
+static INTEGRITY_SIGNING_KEY: LazyLock<Option<[u8; 64]>> = LazyLock::new(|| {
+    let secret = State::shared()
+        .get_config("database")
+        .and_then(|config| config.get_table("integrity"))
+        .and_then(|config| config.get_str("signing-key"))?;
+    let checksum = crypto::digest(secret.as_bytes());
+    Some(crypto::derive_key("ZINO:ORM:INTEGRITY", &checksum))
+});
+
+/// Encryption keys for the fields. The first one is used for encrypting the new values.
+static ENCRYPTION_KEYS: LazyLock<Vec<(String, [u8; 64])>> = LazyLock::new(|| {
+    let app_config = State::shared().config();
+    let config = app_config
+        .get_table("database")
+        .and_then(|config| config.get_table("encryption"));
+    let mut keys = Vec::new();
+    if let Some(config) = config {
+        let key_id = config.get_str("key-id").unwrap_or_default();
+        if let Some(secrets) = config.get_table("keys") {
+            for (id, secret) in secrets.iter() {
+                if let Some(secret) = secret.as_str() {
+                    let checksum = crypto::digest(secret.as_bytes());
+                    let key = crypto::derive_key("ZINO:ORM:ENCRYPTION", &checksum);
+                    if id == key_id {
+                        keys.insert(0, (id.to_owned(), key));
+                    } else {
+                        keys.push((id.to_owned(), key));
+                    }
+                }
+            }
+        }
+    }
+    if keys.is_empty() {
+        keys.push(("0".to_owned(), *SECRET_KEY));
+    }
+    keys
+});
+
 /// Secret key.
 static SECRET_KEY: LazyLock<[u8; 64]> = LazyLock::new(|| {
     let app_config = State::shared().config();
@@ -94,3 +426,31 @@ static SECRET_KEY: LazyLock<[u8; 64]> = LazyLock::new(|| {
     let info = config.get_str("info").unwrap_or("ZINO:ORM");
     crypto::derive_key(info, &checksum)
 });
+
+#[cfg(test)]
+mod tests {
+    use super::{decrypt_value, encrypt_filter_values, encrypt_value};
+
+    #[test]
+    fn it_encrypts_field_values() {
+        let keys = vec![("2".to_owned(), [2; 64]), ("1".to_owned(), [1; 64])];
+        let ciphertext = encrypt_value("secret", "1", &keys[1].1, false).unwrap();
+        assert!(ciphertext.starts_with("enc:1:"));
+        assert_eq!(
+            decrypt_value(&ciphertext, &keys).unwrap().unwrap(),
+            "secret"
+        );
+        assert!(decrypt_value("secret", &keys).is_none());
+
+        // A plaintext with the prefix is not regarded as a valid ciphertext.
+        assert!(decrypt_value("enc:1:forged", &keys).unwrap().is_err());
+        assert!(decrypt_value("enc:3:forged", &keys).unwrap().is_err());
+
+        // The filter matches the ciphertexts produced by the rotated keys.
+        let values = ["secret".to_owned()];
+        let alternatives = encrypt_filter_values("extra.token", &values, &keys).unwrap();
+        assert_eq!(alternatives.len(), 2);
+        let expected = encrypt_value("secret", "1", &keys[1].1, true).unwrap();
+        assert_eq!(alternatives[1]["extra.token"], expected.as_str());
+    }
+}
@@ -79,29 +79,30 @@
 //!
 //! # Query operators
 //!
-//! | Name       | MySQL               | PostgreSQL       | SQLite                |
-//! |------------|---------------------|------------------|-----------------------|
-//! | `$and`     | `AND`               | `AND`            | `AND`                 |
-//! | `$or`      | `OR`                | `OR`             | `OR`                  |
-//! | `$not`     | `NOT`               | `NOT`            | `NOT`                 |
-//! | `$rand`    | `rand()`            | `random()`       | `abs(random())`       |
-//! | `$text`    | `match() against()` | `to_tsvector()`  | `MATCH`               |
-//! | `$ovlp`    | `overlaps()`        | `OVERLAPS`       | N/A                   |
-//! | `$eq`      | `=`                 | `=`              | `=`                   |
-//! | `$ne`      | `<>`                | `<>`             | `<>`                  |
-//! | `$lt`      | `<`                 | `<`              | `<`                   |
-//! | `$le`      | `<=`                | `<=`             | `<=`                  |
-//! | `$gt`      | `>`                 | `>`              | `>`                   |
-//! | `$ge`      | `>=`                | `>=`             | `>=`                  |
-//! | `$in`      | `IN`                | `IN`             | `IN`                  |
-//! | `$nin`     | `NOT IN`            | `NOT IN`         | `NOT IN`              |
-//! | `$betw`    | `BETWEEN AND`       | `BETWEEN AND`    | `BETWEEN AND`         |
-//! | `$like`    | `LIKE`              | `LIKE`           | `LIKE`                |
-//! | `$ilike`   | `ILIKE`             | `ILIKE`          | N/A                   |
-//! | `$rlike`   | `RLIKE`             | `~*`             | `REGEXP`              |
-//! | `$glob`    | N/A                 | N/A              | `GLOB`                |
-//! | `$is`      | `IS`                | `IS`             | `IS`                  |
-//! | `$size`    | `json_length()`     | `array_length()` | `json_array_length()` |
+//! | Name       | MySQL                  | PostgreSQL       | SQLite                |
+//! |------------|------------------------|------------------|-----------------------|
+//! | `$and`     | `AND`                  | `AND`            | `AND`                 |
+//! | `$or`      | `OR`                   | `OR`             | `OR`                  |
+//! | `$not`     | `NOT`                  | `NOT`            | `NOT`                 |
+//! | `$rand`    | `rand()`               | `random()`       | `abs(random())`       |
+//! | `$text`    | `match() against()`    | `to_tsvector()`  | `MATCH`               |
+//! | `$ovlp`    | `overlaps()`           | `OVERLAPS`       | N/A                   |
+//! | `$near`    | `ST_Distance_Sphere()` | `<@ box()`       | `json_extract()`      |
+//! | `$eq`      | `=`                    | `=`              | `=`                   |
+//! | `$ne`      | `<>`                   | `<>`             | `<>`                  |
+//! | `$lt`      | `<`                    | `<`              | `<`                   |
+//! | `$le`      | `<=`                   | `<=`             | `<=`                  |
+//! | `$gt`      | `>`                    | `>`              | `>`                   |
+//! | `$ge`      | `>=`                   | `>=`             | `>=`                  |
+//! | `$in`      | `IN`                   | `IN`             | `IN`                  |
+//! | `$nin`     | `NOT IN`               | `NOT IN`         | `NOT IN`              |
+//! | `$betw`    | `BETWEEN AND`          | `BETWEEN AND`    | `BETWEEN AND`         |
+//! | `$like`    | `LIKE`                 | `LIKE`           | `LIKE`                |
+//! | `$ilike`   | `ILIKE`                | `ILIKE`          | N/A                   |
+//! | `$rlike`   | `RLIKE`                | `~*`             | `REGEXP`              |
+//! | `$glob`    | N/A                    | N/A              | `GLOB`                |
+//! | `$is`      | `IS`                   | `IS`             | `IS`                  |
+//! | `$size`    | `json_length()`        | `array_length()` | `json_array_length()` |
 //!
 //! [`Mongoose`]: https://mongoosejs.com/
 //! [`Prisma`]: https://www.prisma.io/
@@ -116,7 +117,10 @@ use std::sync::{
 };
 
 mod accessor;
+mod bulk;
+mod cache;
 mod column;
+mod conflict;
 mod executor;
 mod helper;
 mod manager;
@@ -127,6 +131,9 @@ mod schema;
 mod transaction;
 
 pub use accessor::ModelAccessor;
+pub use bulk::BulkOutcome;
+pub use cache::{CacheBackend, MemoryCache, QueryCache};
+pub use conflict::OptimisticLockConflict;
 pub use executor::Executor;
 pub use helper::ModelHelper;
 pub use manager::PoolManager;
@@ -139,8 +146,10 @@ mod decode;
 #[cfg(feature = "orm-sqlx")]
 mod scalar;
 
+#[cfg(feature = "accessor")]
+pub use cache::AccessorCache;
 #[cfg(feature = "orm-sqlx")]
-pub use decode::{decode, decode_array, decode_decimal, decode_uuid};
+pub use decode::{decode, decode_array, decode_decimal, decode_point, decode_uuid};
 #[cfg(feature = "orm-sqlx")]
 pub use scalar::ScalarQuery;
 
@@ -11,7 +11,8 @@ pub(super) trait MutationExt<DB> {
 impl MutationExt<DatabaseDriver> for Mutation {
     fn format_updates<M: Schema>(&self) -> String {
         let updates = self.updates();
-        if updates.is_empty() {
+        let read_only_updates = self.read_only_updates();
+        if updates.is_empty() && read_only_updates.is_empty() {
             return String::new();
         }
 
@@ -96,6 +97,13 @@ impl MutationExt<DatabaseDriver> for Mutation {
                 }
             }
         }
+        for (key, value) in read_only_updates.iter() {
+            if let Some(col) = M::get_column(key).filter(|col| col.name() != M::PRIMARY_KEY_NAME) {
+                let key = Query::format_field(key);
+                let value = col.encode_value(Some(value));
+                mutations.push(format!(r#"{key} = {value}"#));
+            }
+        }
         mutations.join(", ")
     }
 }
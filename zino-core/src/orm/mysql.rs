@@ -51,6 +51,7 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
             "Vec<u8>" => "BLOB",
             "Vec<String>" | "Vec<Uuid>" | "Vec<u64>" | "Vec<i64>" | "Vec<u32>" | "Vec<i32>"
             | "Map" => "JSON",
+            "Option<(f64, f64)>" => "POINT",
             _ => "TEXT",
         }
     }
@@ -79,6 +80,13 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                         self.format_value(value)
                     }
                 }
+                JsonValue::Array(value) if self.is_point_type() => {
+                    if let [JsonValue::Number(lng), JsonValue::Number(lat)] = value.as_slice() {
+                        format!("POINT({lng}, {lat})").into()
+                    } else {
+                        "NULL".into()
+                    }
+                }
                 JsonValue::Array(value) => {
                     let values = value
                         .iter()
@@ -472,6 +480,9 @@ impl DecodeRow<DatabaseRow> for Map {
                         }
                     }
                     "JSON" => decode_raw::<JsonValue>(field, raw_value)?,
+                    "GEOMETRY" => super::decode::decode_point(row, field)?
+                        .map(|(lng, lat)| JsonValue::from(vec![lng, lat]))
+                        .unwrap_or_default(),
                     #[cfg(feature = "orm-mariadb")]
                     "TEXT" | "LONGTEXT" => {
                         // In MariaDB, JSON is just an alias for LONGTEXT.
@@ -670,9 +681,18 @@ impl QueryExt<DatabaseDriver> for Query {
     fn parse_text_search(filter: &Map) -> Option<String> {
         let fields = filter.parse_str_array("$fields")?;
         filter.parse_string("$search").map(|search| {
-            let fields = fields.join(",");
+            let fields = fields
+                .iter()
+                .map(|field| Query::format_field(field))
+                .collect::<Vec<_>>()
+                .join(",");
             let search = Query::escape_string(search.as_ref());
             format!("match({fields}) against({search})")
         })
     }
+
+    #[inline]
+    fn format_text_rank(filter: &Map) -> Option<String> {
+        Self::parse_text_search(filter)
+    }
 }
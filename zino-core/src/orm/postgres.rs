@@ -54,6 +54,7 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
             "Vec<u64>" | "Vec<i64>" => "BIGINT[]",
             "Vec<u32>" | "Vec<i32>" => "INT[]",
             "Map" => "JSONB",
+            "Option<(f64, f64)>" => "POINT",
             _ => "TEXT",
         }
     }
@@ -82,6 +83,13 @@ impl<'c> EncodeColumn<DatabaseDriver> for Column<'c> {
                         self.format_value(value)
                     }
                 }
+                JsonValue::Array(value) if self.is_point_type() => {
+                    if let [JsonValue::Number(lng), JsonValue::Number(lat)] = value.as_slice() {
+                        format!("point({lng}, {lat})").into()
+                    } else {
+                        "NULL".into()
+                    }
+                }
                 JsonValue::Array(value) => {
                     let values = value
                         .iter()
@@ -490,6 +498,9 @@ impl DecodeRow<DatabaseRow> for Map {
                             .into()
                     }
                     "JSONB" | "JSON" => decode_raw::<JsonValue>(field, raw_value)?,
+                    "POINT" => super::decode::decode_point(row, field)?
+                        .map(|(lng, lat)| JsonValue::from(vec![lng, lat]))
+                        .unwrap_or_default(),
                     _ => decode_raw::<String>(field, raw_value)?.into(),
                 }
             };
@@ -668,13 +679,37 @@ impl QueryExt<DatabaseDriver> for Query {
     }
 
     fn parse_text_search(filter: &Map) -> Option<String> {
-        let fields = filter.parse_str_array("$fields")?;
-        filter.parse_string("$search").map(|search| {
-            let text = fields.join(" || ' ' || ");
-            let lang = filter
-                .parse_string("$language")
-                .unwrap_or_else(|| "english".into());
-            format!("to_tsvector('{lang}', {text}) @@ websearch_to_tsquery('{lang}', '{search}')")
-        })
+        let (text, lang, search) = format_text_search(filter)?;
+        Some(format!(
+            "to_tsvector({lang}, {text}) @@ websearch_to_tsquery({lang}, {search})"
+        ))
     }
+
+    fn format_text_rank(filter: &Map) -> Option<String> {
+        let (text, lang, search) = format_text_search(filter)?;
+        Some(format!(
+            "ts_rank(to_tsvector({lang}, {text}), websearch_to_tsquery({lang}, {search}))"
+        ))
+    }
+}
+
+/// Formats the text, language and search terms of the text search filter.
+/// The text is concatenated in the same way as the text search index.
+fn format_text_search(filter: &Map) -> Option<(String, String, String)> {
+    let fields = filter.parse_str_array("$fields")?;
+    let search = filter.parse_string("$search")?;
+    let text = fields
+        .iter()
+        .map(|field| {
+            let field = Query::format_field(field);
+            format!("coalesce({field}, '')")
+        })
+        .collect::<Vec<_>>()
+        .join(" || ' ' || ");
+    let lang = filter
+        .parse_string("$language")
+        .unwrap_or_else(|| "english".into());
+    let lang = Query::escape_string(lang.as_ref());
+    let search = Query::escape_string(search.as_ref());
+    Some((text, lang, search))
 }
@@ -53,12 +53,84 @@ pub(super) trait QueryExt<DB> {
     /// Parses text search filter.
     fn parse_text_search(filter: &Map) -> Option<String>;
 
+    /// Formats the expression to order by the relevance to the text search filter,
+    /// or returns `None` if it is not supported by the database.
+    #[inline]
+    fn format_text_rank(filter: &Map) -> Option<String> {
+        let _ = filter;
+        None
+    }
+
     /// Escapes a string.
     #[inline]
     fn escape_string(value: impl Display) -> String {
         format!("'{}'", value.to_string().replace('\'', "''"))
     }
 
+    /// Formats the expression to order by the distance to the point `(lng, lat)`,
+    /// or returns `None` if it is not supported by the database.
+    fn format_point_distance(field: &str, point: (f64, f64)) -> Option<String> {
+        let field = Self::format_field(field);
+        let (lng, lat) = point;
+        if cfg!(any(
+            feature = "orm-mariadb",
+            feature = "orm-mysql",
+            feature = "orm-tidb"
+        )) {
+            Some(format!("ST_Distance_Sphere({field}, POINT({lng}, {lat}))"))
+        } else if cfg!(feature = "orm-postgres") {
+            Some(format!("{field} <-> point({lng}, {lat})"))
+        } else {
+            None
+        }
+    }
+
+    /// Formats the condition that the point field is within the radius in meters.
+    ///
+    /// A bounding box is used to narrow down the candidates, followed by a great-circle
+    /// distance check. For SQLite, only the bounding box is checked.
+    fn format_point_radius(field: &str, point: (f64, f64), radius: f64) -> String {
+        let field = Self::format_field(field);
+        let (lng, lat) = point;
+        let delta_lat = radius / METERS_PER_DEGREE;
+        let cos_lat = lat.to_radians().cos();
+        let delta_lng = if cos_lat > 1e-6 {
+            (delta_lat / cos_lat).min(180.0)
+        } else {
+            180.0
+        };
+        let (min_lng, max_lng) = (lng - delta_lng, lng + delta_lng);
+        let (min_lat, max_lat) = (lat - delta_lat, lat + delta_lat);
+        if cfg!(any(
+            feature = "orm-mariadb",
+            feature = "orm-mysql",
+            feature = "orm-tidb"
+        )) {
+            format!(
+                "(ST_X({field}) BETWEEN {min_lng} AND {max_lng} \
+                    AND ST_Y({field}) BETWEEN {min_lat} AND {max_lat} \
+                    AND ST_Distance_Sphere({field}, POINT({lng}, {lat})) <= {radius})"
+            )
+        } else if cfg!(feature = "orm-postgres") {
+            let lat_radians = lat.to_radians();
+            let haversine = format!(
+                "2 * {EARTH_RADIUS} * asin(sqrt(\
+                    power(sin(radians({field}[1] - {lat}) / 2), 2) + \
+                    cos({lat_radians}) * cos(radians({field}[1])) * \
+                    power(sin(radians({field}[0] - {lng}) / 2), 2)))"
+            );
+            format!(
+                "({field} <@ box(point({min_lng}, {min_lat}), point({max_lng}, {max_lat})) \
+                    AND {haversine} <= {radius})"
+            )
+        } else {
+            format!(
+                "(json_extract({field}, '$[0]') BETWEEN {min_lng} AND {max_lng} \
+                    AND json_extract({field}, '$[1]') BETWEEN {min_lat} AND {max_lat})"
+            )
+        }
+    }
+
     /// Formats projection fields.
     fn format_projection(&self) -> Cow<'_, str> {
         let fields = self.query_fields();
@@ -132,6 +204,19 @@ pub(super) trait QueryExt<DB> {
                         logical_and_conditions.push(condition);
                     }
                 }
+                "$near" => {
+                    if let Some(values) = value.as_array() {
+                        if let [JsonValue::String(field), lng, lat, radius] = values.as_slice() {
+                            if let (Some(lng), Some(lat), Some(radius)) =
+                                (lng.as_f64(), lat.as_f64(), radius.as_f64())
+                            {
+                                let condition =
+                                    Self::format_point_radius(field, (lng, lat), radius);
+                                logical_and_conditions.push(condition);
+                            }
+                        }
+                    }
+                }
                 "$ovlp" => {
                     if let Some(values) = value.parse_str_array() {
                         if let [start_field, end_field, start_value, end_value] = values.as_slice()
@@ -350,3 +435,36 @@ pub(super) trait QueryExt<DB> {
         format!("LIMIT {limit} OFFSET {offset}")
     }
 }
+
+/// Mean radius of the Earth in meters.
+pub(super) const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Approximate length of one degree of latitude in meters.
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+#[cfg(test)]
+mod tests {
+    use super::{QueryExt, METERS_PER_DEGREE};
+    use crate::{model::Query, orm::DatabaseDriver};
+
+    #[test]
+    fn it_formats_point_radius() {
+        let condition = <Query as QueryExt<DatabaseDriver>>::format_point_radius(
+            "location",
+            (116.4, 39.9),
+            1000.0,
+        );
+        let delta_lat = 1000.0 / METERS_PER_DEGREE;
+        assert!(condition.contains(&(39.9 - delta_lat).to_string()));
+        assert!(condition.contains(&(39.9 + delta_lat).to_string()));
+        assert!(condition.contains("location"));
+
+        // The longitude range is widened near the poles.
+        let condition = <Query as QueryExt<DatabaseDriver>>::format_point_radius(
+            "location",
+            (0.0, 90.0),
+            1000.0,
+        );
+        assert!(condition.contains("-180"));
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
     column::ColumnExt, mutation::MutationExt, query::QueryExt, ConnectionPool, DatabaseRow,
-    Executor, GlobalPool, ModelHelper,
+    Executor, GlobalPool, ModelHelper, QueryCache,
 };
 use crate::{
     bail,
@@ -340,9 +340,12 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             for col in columns {
                 if let Some(index_type) = col.index_type() {
                     let column_name = col.name();
-                    if matches!(index_type, "fulltext" | "text") {
+                    if index_type == "fulltext" || index_type.starts_with("text") {
                         text_search_columns.push(column_name);
-                    } else if matches!(index_type, "unique" | "spatial") {
+                    } else if index_type == "unique"
+                        || (index_type == "spatial" && col.is_not_null())
+                    {
+                        // The spatial index is only supported for the non-null columns.
                         let index_type = index_type.to_uppercase();
                         let sql = format!(
                             "CREATE {index_type} INDEX {table_name}_{column_name}_index \
@@ -385,6 +388,11 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
                         );
                         rows = pool.execute(&sql).await?.rows_affected().max(rows);
                     } else {
+                        let index_type = if index_type == "spatial" {
+                            "gist"
+                        } else {
+                            index_type
+                        };
                         let sort_order = if index_type == "btree" { " DESC" } else { "" };
                         let sql = format!(
                             "CREATE INDEX IF NOT EXISTS {table_name}_{column_name}_index \
@@ -464,6 +472,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         let (last_insert_id, rows_affected) = Query::parse_query_result(query_result);
         let success = rows_affected == 1;
         if let Some(last_insert_id) = last_insert_id {
@@ -483,10 +492,11 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
     }
 
     /// Prepares the SQL to insert many models into the table.
-    async fn prepare_insert_many(models: Vec<Self>) -> Result<QueryContext, Error> {
+    async fn prepare_insert_many(mut models: Vec<Self>) -> Result<QueryContext, Error> {
         if models.is_empty() {
             bail!("the list of models to be inserted should be nonempty");
         }
+        Self::before_insert_many(&mut models).await?;
 
         let columns = Self::columns();
         let mut values = Vec::with_capacity(models.len());
@@ -523,6 +533,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         ctx.set_query_result(query_result.rows_affected(), true);
         Self::after_scan(&ctx).await?;
         Ok(ctx)
@@ -568,6 +579,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
         ctx.set_query_result(rows_affected, success);
@@ -630,6 +642,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected <= 1;
         ctx.set_query_result(rows_affected, success);
@@ -673,6 +686,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         ctx.set_query_result(query_result.rows_affected(), true);
         Self::after_scan(&ctx).await?;
         Self::after_mutation(&ctx).await?;
@@ -738,6 +752,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         let (last_insert_id, rows_affected) = Query::parse_query_result(query_result);
         let success = rows_affected == 1;
         if let Some(last_insert_id) = last_insert_id {
@@ -756,6 +771,101 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
     }
 
+    /// Prepares the SQL to update or insert many models into the table.
+    ///
+    /// The models which do not exist in the table run through the hook
+    /// [`before_insert()`](ModelHooks::before_insert) instead of `before_upsert()`.
+    /// The `version` of an existing row is increased instead of being overwritten.
+    async fn prepare_upsert_many(models: Vec<Self>) -> Result<QueryContext, Error> {
+        if models.is_empty() {
+            bail!("the list of models to be upserted should be nonempty");
+        }
+
+        let primary_key_name = Self::PRIMARY_KEY_NAME;
+        let primary_key_values = models
+            .iter()
+            .map(|model| model.primary_key_value())
+            .collect::<Vec<_>>();
+        let mut query =
+            Query::from_entry(primary_key_name, Map::from_entry("$in", primary_key_values));
+        query.allow_fields(&[primary_key_name]);
+        query.set_limit(models.len());
+
+        let existing_keys = Self::find::<Map>(&query)
+            .await?
+            .into_iter()
+            .filter_map(|mut map| map.remove(primary_key_name))
+            .map(|value| value.to_string_unquoted())
+            .collect::<Vec<_>>();
+        let columns = Self::columns();
+        let mut values = Vec::with_capacity(models.len());
+        for mut model in models.into_iter() {
+            let primary_key = model.primary_key().to_string();
+            let _model_data = if existing_keys.contains(&primary_key) {
+                model.before_upsert().await?
+            } else {
+                model.before_insert().await?
+            };
+
+            let map = model.into_map();
+            let entries = columns
+                .iter()
+                .map(|col| col.encode_value(map.get(col.name())))
+                .collect::<Vec<_>>()
+                .join(", ");
+            values.push(format!("({entries})"));
+        }
+
+        let table_name = Query::table_name_escaped::<Self>();
+        let fields = Self::fields();
+        let is_mysql = cfg!(any(
+            feature = "orm-mariadb",
+            feature = "orm-mysql",
+            feature = "orm-tidb"
+        ));
+        let mutations = format_upsert_assignments(
+            &table_name,
+            fields,
+            Self::read_only_fields(),
+            is_mysql,
+            |field| Query::format_field(field).into_owned(),
+        );
+        let fields = fields.join(", ");
+        let values = values.join(", ");
+        let sql = if is_mysql {
+            format!(
+                "INSERT INTO {table_name} ({fields}) VALUES {values} \
+                    ON DUPLICATE KEY UPDATE {mutations};"
+            )
+        } else {
+            format!(
+                "INSERT INTO {table_name} ({fields}) VALUES {values} \
+                    ON CONFLICT ({primary_key_name}) DO UPDATE SET {mutations};"
+            )
+        };
+        let mut ctx = Self::before_scan(&sql).await?;
+        ctx.set_query(sql);
+        if cfg!(debug_assertions) && super::DEBUG_ONLY.load(Relaxed) {
+            ctx.cancel();
+        }
+        Ok(ctx)
+    }
+
+    /// Updates or inserts many models into the table.
+    async fn upsert_many(models: Vec<Self>) -> Result<QueryContext, Error> {
+        let mut ctx = Self::prepare_upsert_many(models).await?;
+        if ctx.is_cancelled() {
+            return Ok(ctx);
+        }
+
+        let pool = Self::acquire_writer().await?.pool();
+        let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
+        ctx.set_query_result(query_result.rows_affected(), true);
+        Self::after_scan(&ctx).await?;
+        Ok(ctx)
+    }
+
     /// Prepares the SQL to delete the model in the table.
     async fn prepare_delete() -> Result<QueryContext, Error> {
         let primary_key_name = Self::PRIMARY_KEY_NAME;
@@ -789,6 +899,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         let pool = Self::acquire_writer().await?.pool();
         let primary_key = self.primary_key();
         let query_result = pool.execute_with(ctx.query(), &[primary_key]).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
         ctx.add_argument(primary_key);
@@ -834,6 +945,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected <= 1;
         ctx.set_query_result(rows_affected, success);
@@ -873,6 +985,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute(ctx.query()).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         ctx.set_query_result(query_result.rows_affected(), true);
         Self::after_scan(&ctx).await?;
         Self::after_query(&ctx).await?;
@@ -1314,6 +1427,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
             .collect::<Vec<_>>();
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute_with(ctx.query(), &arguments).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         ctx.append_arguments(&mut arguments);
         ctx.set_query_result(query_result.rows_affected(), true);
         Self::after_scan(&ctx).await?;
@@ -1428,6 +1542,7 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
 
         let pool = Self::acquire_writer().await?.pool();
         let query_result = pool.execute_with(ctx.query(), &[primary_key]).await?;
+        QueryCache::invalidate(Self::model_name()).await;
         let rows_affected = query_result.rows_affected();
         let success = rows_affected == 1;
         ctx.add_argument(primary_key);
@@ -1618,3 +1733,64 @@ pub trait Schema: 'static + Send + Sync + ModelHooks {
         }
     }
 }
+
+/// Formats the assignments of the upsert for the writable fields.
+/// The `version` field is increased instead of being overwritten,
+/// so that the optimistic concurrency control keeps working.
+fn format_upsert_assignments(
+    table_name: &str,
+    fields: &[&str],
+    read_only_fields: &[&str],
+    is_mysql: bool,
+    format_field: impl Fn(&str) -> String,
+) -> String {
+    fields
+        .iter()
+        .filter(|field| !read_only_fields.contains(field))
+        .map(|&name| {
+            let field = format_field(name);
+            if name == "version" {
+                if is_mysql {
+                    format!("{field} = {field} + 1")
+                } else {
+                    format!("{field} = {table_name}.{field} + 1")
+                }
+            } else if is_mysql {
+                format!("{field} = VALUES({field})")
+            } else {
+                format!("{field} = excluded.{field}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_upsert_assignments;
+
+    #[test]
+    fn it_increases_version_in_upsert() {
+        let fields = ["id", "name", "updated_at", "version"];
+        let read_only_fields = ["id"];
+        let format_field = |field: &str| format!("\"{field}\"");
+        let assignments = format_upsert_assignments(
+            "\"record\"",
+            &fields,
+            &read_only_fields,
+            false,
+            format_field,
+        );
+        assert_eq!(
+            assignments,
+            "\"name\" = excluded.\"name\", \"updated_at\" = excluded.\"updated_at\", \
+                \"version\" = \"record\".\"version\" + 1"
+        );
+
+        let format_field = |field: &str| format!("`{field}`");
+        let assignments =
+            format_upsert_assignments("`record`", &fields, &read_only_fields, true, format_field);
+        assert!(assignments.ends_with("`version` = `version` + 1"));
+        assert!(!assignments.contains("`id`"));
+    }
+}
@@ -638,7 +638,11 @@ impl QueryExt<DatabaseDriver> for Query {
     fn parse_text_search(filter: &Map) -> Option<String> {
         let fields = filter.parse_str_array("$fields")?;
         filter.parse_string("$search").map(|search| {
-            let fields = fields.join(",");
+            let fields = fields
+                .iter()
+                .map(|field| Query::format_field(field))
+                .collect::<Vec<_>>()
+                .join(",");
             let search = Query::escape_string(search.as_ref());
             format!("{fields} MATCH {search}")
         })
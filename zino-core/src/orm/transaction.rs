@@ -1,5 +1,6 @@
 use super::{
     executor::Executor, mutation::MutationExt, query::QueryExt, schema::Schema, DatabaseDriver,
+    QueryCache,
 };
 use crate::{
     error::Error,
@@ -156,6 +157,8 @@ where
 
         // Commits the transaction
         transaction.commit().await?;
+        QueryCache::invalidate(Self::model_name()).await;
+        QueryCache::invalidate(S::model_name()).await;
         Ok(total_rows)
     }
 
@@ -203,6 +206,8 @@ where
 
         // Commits the transaction
         transaction.commit().await?;
+        QueryCache::invalidate(Self::model_name()).await;
+        QueryCache::invalidate(S::model_name()).await;
         Ok(total_rows)
     }
 
@@ -243,6 +248,8 @@ where
 
         // Commits the transaction
         transaction.commit().await?;
+        QueryCache::invalidate(Self::model_name()).await;
+        QueryCache::invalidate(S::model_name()).await;
         Ok(total_rows)
     }
 }
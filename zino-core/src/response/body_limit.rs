@@ -0,0 +1,135 @@
+use super::BodyStream;
+use crate::{error::Error, extension::TomlTableExt, state::State, warn, LazyLock};
+use bytes::Bytes;
+use futures::{future, StreamExt};
+use std::io;
+
+/// A writer which fails once the buffer would exceed the max size.
+pub(super) struct LimitedWriter {
+    /// Buffer.
+    buffer: Vec<u8>,
+    /// Max size of the buffer in bytes.
+    limit: usize,
+}
+
+impl LimitedWriter {
+    /// Creates a new instance with the capacity, which is capped by the limit.
+    #[inline]
+    pub(super) fn with_capacity(capacity: usize, limit: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(capacity.min(limit)),
+            limit,
+        }
+    }
+
+    /// Consumes `self` and returns the buffer.
+    #[inline]
+    pub(super) fn into_inner(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+impl io::Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.buffer.len() + buf.len() > self.limit {
+            let message = format!(
+                "the response body exceeds the max size of {} bytes",
+                self.limit
+            );
+            return Err(io::Error::other(message));
+        }
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Checks the size of the response body.
+pub(super) fn check_body_size(size: usize, limit: usize) -> Result<(), Error> {
+    if size > limit {
+        Err(warn!(
+            "the response body of {} bytes exceeds the max size of {} bytes",
+            size, limit
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Limits the total size of the streaming body. The stream is terminated with an error
+/// once the limit is exceeded.
+pub(super) fn limit_body_stream(stream: BodyStream, limit: usize) -> BodyStream {
+    stream
+        .scan((0, false), move |(size, exceeded), result| {
+            if *exceeded {
+                return future::ready(None);
+            }
+            let result = result.and_then(|chunk: Bytes| {
+                *size += chunk.len();
+                check_body_size(*size, limit).map(|_| chunk)
+            });
+            *exceeded = result.is_err();
+            future::ready(Some(result))
+        })
+        .boxed()
+}
+
+/// Default max size of the buffered response body in bytes.
+pub(super) static MAX_BODY_SIZE: LazyLock<usize> = LazyLock::new(|| {
+    State::shared()
+        .get_config("response")
+        .and_then(|config| config.get_usize("max-body-size"))
+        .unwrap_or(128 * 1024 * 1024)
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{limit_body_stream, LimitedWriter};
+    use crate::{
+        json,
+        response::{Response, StatusCode},
+    };
+    use bytes::Bytes;
+    use futures::{executor::block_on, stream, StreamExt};
+
+    #[test]
+    fn it_limits_body_size() {
+        let value = serde_json::json!({ "name": "zino" });
+        let mut writer = LimitedWriter::with_capacity(1024, 32);
+        assert!(serde_json::to_writer(&mut writer, &value).is_ok());
+        assert_eq!(writer.into_inner(), br#"{"name":"zino"}"#);
+
+        let mut writer = LimitedWriter::with_capacity(1024, 8);
+        assert!(serde_json::to_writer(&mut writer, &value).is_err());
+    }
+
+    #[test]
+    fn it_rejects_oversized_response_body() {
+        let data = json!([{ "id": 1, "name": "alice" }, { "id": 2, "name": "bob" }]);
+        for content_type in ["application/json", "text/csv", "application/jsonlines"] {
+            let mut res = Response::new(StatusCode::OK);
+            res.set_json_data(data.clone());
+            res.set_content_type(content_type);
+            res.set_max_body_size(16);
+            assert!(res.read_bytes().is_err(), "{content_type}");
+
+            res.set_max_body_size(1024);
+            assert!(res.read_bytes().is_ok(), "{content_type}");
+        }
+    }
+
+    #[test]
+    fn it_limits_body_stream() {
+        let chunks = ["hello", " ", "world", "!"].map(|s| Ok(Bytes::from(s)));
+        let stream = limit_body_stream(stream::iter(chunks).boxed(), 8);
+        let results = block_on(stream.collect::<Vec<_>>());
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), "hello");
+        assert_eq!(results[1].as_ref().unwrap(), " ");
+        assert!(results[2].is_err());
+    }
+}
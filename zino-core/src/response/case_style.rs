@@ -0,0 +1,93 @@
+use crate::JsonValue;
+use convert_case::{Boundary, Case, Casing};
+
+/// Boundaries to split the words of a key. Digits are not treated as boundaries
+/// so that the keys like `sha256` are kept intact.
+const WORD_BOUNDARIES: [Boundary; 5] = [
+    Boundary::Underscore,
+    Boundary::Hyphen,
+    Boundary::Space,
+    Boundary::LowerUpper,
+    Boundary::Acronym,
+];
+
+/// Case styles for the keys of the response data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CaseStyle {
+    /// `snake_case`.
+    Snake,
+    /// `camelCase`.
+    Camel,
+    /// `PascalCase`.
+    Pascal,
+    /// `kebab-case`.
+    Kebab,
+}
+
+impl CaseStyle {
+    /// Converts the key to the case style.
+    fn convert_key(self, key: &str) -> String {
+        if key.starts_with('$') {
+            return key.to_owned();
+        }
+
+        let case = match self {
+            Self::Snake => Case::Snake,
+            Self::Camel => Case::Camel,
+            Self::Pascal => Case::Pascal,
+            Self::Kebab => Case::Kebab,
+        };
+        key.with_boundaries(&WORD_BOUNDARIES).to_case(case)
+    }
+
+    /// Rewrites the keys of the JSON value recursively.
+    pub(super) fn convert(self, value: &mut JsonValue) {
+        match value {
+            JsonValue::Object(map) => {
+                let entries = std::mem::take(map);
+                for (key, mut value) in entries {
+                    self.convert(&mut value);
+                    map.insert(self.convert_key(&key), value);
+                }
+            }
+            JsonValue::Array(vec) => {
+                for value in vec {
+                    self.convert(value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CaseStyle;
+
+    #[test]
+    fn it_converts_case_style() {
+        let mut value = serde_json::json!({
+            "user_id": 1,
+            "createdAt": "2024-07-01",
+            "HTTPStatus": 200,
+            "sha256": "digest",
+            "items": [{ "item_name": "a" }],
+        });
+        CaseStyle::Camel.convert(&mut value);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "userId": 1,
+                "createdAt": "2024-07-01",
+                "httpStatus": 200,
+                "sha256": "digest",
+                "items": [{ "itemName": "a" }],
+            })
+        );
+
+        CaseStyle::Snake.convert(&mut value);
+        assert_eq!(value["http_status"], 200);
+        assert_eq!(value["created_at"], "2024-07-01");
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{error::Error, extension::TomlTableExt, state::State, LazyLock};
+use bytes::Bytes;
+
+/// Compression algorithms for the response body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompressionAlgo {
+    /// The `gzip` encoding.
+    Gzip,
+    /// The `br` encoding.
+    Brotli,
+    /// The `deflate` encoding.
+    Deflate,
+    /// No compression. This is the default value.
+    #[default]
+    Identity,
+}
+
+impl CompressionAlgo {
+    /// Returns the token used in the `content-encoding` header.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Brotli => "br",
+            Self::Deflate => "deflate",
+            Self::Identity => "identity",
+        }
+    }
+
+    /// Returns `true` if the algorithm is supported by the enabled features.
+    #[inline]
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Self::Gzip | Self::Deflate => cfg!(feature = "compression-gzip"),
+            Self::Brotli => cfg!(feature = "compression-brotli"),
+            Self::Identity => true,
+        }
+    }
+
+    /// Negotiates the algorithm with the `accept-encoding` header value.
+    /// The supported encoding with the highest quality is chosen,
+    /// and `Brotli` is preferred over `Gzip` and `Deflate` for the same quality.
+    pub fn negotiate(accept_encoding: &str) -> Self {
+        let mut algo = Self::Identity;
+        let mut max_quality = 0.0;
+        for entry in accept_encoding.split(',') {
+            let mut parts = entry.split(';');
+            let coding = parts.next().unwrap_or_default().trim();
+            let quality = parts
+                .find_map(|s| s.trim().strip_prefix("q="))
+                .and_then(|s| s.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let candidates: &[Self] = match coding {
+                "br" => &[Self::Brotli],
+                "gzip" | "x-gzip" => &[Self::Gzip],
+                "deflate" => &[Self::Deflate],
+                "*" => &[Self::Brotli, Self::Gzip],
+                _ => &[],
+            };
+            for &candidate in candidates {
+                let preferred = quality > max_quality
+                    || (quality == max_quality && candidate.priority() > algo.priority());
+                if quality > 0.0 && candidate.is_supported() && preferred {
+                    algo = candidate;
+                    max_quality = quality;
+                }
+            }
+        }
+        algo
+    }
+
+    /// Returns the priority for the same quality.
+    #[inline]
+    fn priority(&self) -> u8 {
+        match self {
+            Self::Brotli => 3,
+            Self::Gzip => 2,
+            Self::Deflate => 1,
+            Self::Identity => 0,
+        }
+    }
+
+    /// Compresses the bytes. Returns `None` if the algorithm is not supported.
+    pub(super) fn compress(&self, bytes: &[u8]) -> Result<Option<Bytes>, Error> {
+        #[cfg(feature = "compression-gzip")]
+        if matches!(self, Self::Gzip | Self::Deflate) {
+            use flate2::{
+                write::{DeflateEncoder, GzEncoder},
+                Compression,
+            };
+            use std::io::Write;
+
+            let data = if *self == Self::Gzip {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()?
+            } else {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()?
+            };
+            return Ok(Some(data.into()));
+        }
+        #[cfg(feature = "compression-brotli")]
+        if *self == Self::Brotli {
+            use std::io::Write;
+
+            let mut data = Vec::new();
+            {
+                let mut encoder = brotli::CompressorWriter::new(&mut data, 4096, 5, 22);
+                encoder.write_all(bytes)?;
+            }
+            return Ok(Some(data.into()));
+        }
+        let _ = bytes;
+        Ok(None)
+    }
+}
+
+/// Returns `true` if the content type is compressible.
+pub(super) fn is_compressible(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or_default();
+    essence.starts_with("text/")
+        || essence.ends_with("+json")
+        || essence.ends_with("+xml")
+        || matches!(
+            essence,
+            "application/json"
+                | "application/jsonlines"
+                | "application/javascript"
+                | "application/xml"
+                | "application/toml"
+                | "application/msgpack"
+                | "image/svg+xml"
+        )
+}
+
+/// Minimum size of the response body in bytes to be compressed.
+pub(super) static COMPRESSION_THRESHOLD: LazyLock<usize> = LazyLock::new(|| {
+    State::shared()
+        .get_config("response")
+        .and_then(|config| config.get_usize("compression-threshold"))
+        .unwrap_or(1024)
+});
+
+#[cfg(test)]
+mod tests {
+    use super::CompressionAlgo;
+
+    #[test]
+    fn it_negotiates_compression_algo() {
+        let algo = CompressionAlgo::negotiate("gzip;q=0.8, br, identity");
+        let expected = if cfg!(feature = "compression-brotli") {
+            CompressionAlgo::Brotli
+        } else if cfg!(feature = "compression-gzip") {
+            CompressionAlgo::Gzip
+        } else {
+            CompressionAlgo::Identity
+        };
+        assert_eq!(algo, expected);
+        assert_eq!(
+            CompressionAlgo::negotiate("br;q=0"),
+            CompressionAlgo::Identity
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use crate::datetime::DateTime;
+use etag::EntityTag;
+
+/// Returns `true` if the entity tag matches any of the tags in the header value.
+/// The weak comparison is used for `If-None-Match` and the strong one for `If-Match`.
+pub(super) fn match_entity_tags(header: &str, etag: &EntityTag, weak: bool) -> bool {
+    let header = header.trim();
+    if header == "*" {
+        return true;
+    }
+    header
+        .split(',')
+        .filter_map(|tag| tag.trim().parse::<EntityTag>().ok())
+        .any(|tag| {
+            if weak {
+                tag.weak_eq(etag)
+            } else {
+                tag.strong_eq(etag)
+            }
+        })
+}
+
+/// Returns `true` if the last modified time is later than the date in the header value.
+/// The comparison is done with the precision of seconds.
+/// An invalid date is regarded as `None` following RFC 9110.
+pub(super) fn modified_since(header: &str, last_modified: DateTime) -> Option<bool> {
+    let since = DateTime::parse_utc_str(header.trim()).ok()?;
+    Some(last_modified.timestamp() > since.timestamp())
+}
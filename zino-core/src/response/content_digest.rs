@@ -0,0 +1,61 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256, Sha512};
+
+/// Hash algorithms for the `content-digest` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DigestAlgorithm {
+    /// The `sha-256` algorithm.
+    Sha256,
+    /// The `sha-512` algorithm.
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    /// Returns the algorithm key registered in the HTTP Digest Algorithm Values.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha-256",
+            Self::Sha512 => "sha-512",
+        }
+    }
+
+    /// Computes the digest of the data.
+    fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha256 => Sha256::digest(data).to_vec(),
+            Self::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+}
+
+/// Formats the `content-digest` header value as a structured-fields dictionary,
+/// such as `sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:`.
+pub(super) fn format_content_digest(data: &[u8], algorithms: &[DigestAlgorithm]) -> String {
+    algorithms
+        .iter()
+        .map(|algorithm| {
+            let digest = STANDARD.encode(algorithm.digest(data));
+            format!("{}=:{digest}:", algorithm.as_str())
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_content_digest, DigestAlgorithm};
+
+    #[test]
+    fn it_formats_content_digest() {
+        let data = br#"{"hello": "world"}"#;
+        assert_eq!(
+            format_content_digest(data, &[DigestAlgorithm::Sha256]),
+            "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:"
+        );
+        let value =
+            format_content_digest(data, &[DigestAlgorithm::Sha256, DigestAlgorithm::Sha512]);
+        assert!(value.contains(", sha-512=:"));
+    }
+}
@@ -0,0 +1,62 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// Characters to be percent-encoded in the `filename*` parameter,
+/// which are the complement of `attr-char` defined in RFC 5987.
+const ATTR_CHAR_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'!')
+    .remove(b'#')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'+')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'^')
+    .remove(b'_')
+    .remove(b'`')
+    .remove(b'|')
+    .remove(b'~');
+
+/// Formats the `content-disposition` header value with the disposition type and filename.
+///
+/// The `filename` parameter is an ASCII fallback in which the non-ASCII characters,
+/// control characters, quotes and backslashes are replaced with `_`. If the fallback
+/// is lossy, the `filename*` parameter is added with the RFC 5987 encoding.
+pub(super) fn format_content_disposition(disposition: &str, filename: &str) -> String {
+    let fallback = filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() && !matches!(c, '"' | '\\') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if fallback == filename {
+        format!(r#"{disposition}; filename="{fallback}""#)
+    } else {
+        let encoded_filename = utf8_percent_encode(filename, ATTR_CHAR_ENCODE_SET);
+        format!(r#"{disposition}; filename="{fallback}"; filename*=UTF-8''{encoded_filename}"#)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_content_disposition;
+
+    #[test]
+    fn it_formats_content_disposition() {
+        assert_eq!(
+            format_content_disposition("attachment", "report, 2024.csv"),
+            r#"attachment; filename="report, 2024.csv""#
+        );
+        assert_eq!(
+            format_content_disposition("attachment", r#"say "hi".txt"#),
+            r#"attachment; filename="say _hi_.txt"; filename*=UTF-8''say%20%22hi%22.txt"#
+        );
+        assert_eq!(
+            format_content_disposition("inline", "报告.csv"),
+            r#"inline; filename="__.csv"; filename*=UTF-8''%E6%8A%A5%E5%91%8A.csv"#
+        );
+    }
+}
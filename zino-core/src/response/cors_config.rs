@@ -0,0 +1,198 @@
+use crate::SharedString;
+use std::time::Duration;
+
+/// CORS configuration for the response.
+///
+/// By default, any origin is allowed without credentials. If the credentials are allowed,
+/// the wildcard origin is never emitted: the request origin is reflected only if it is
+/// in the allowed origins, and the CORS headers are omitted otherwise. In particular,
+/// no origin is allowed with credentials if the allowed origins are not set.
+#[derive(Debug, Clone, Default)]
+pub struct CorsConfig {
+    /// Allowed origins. An empty list allows any origin.
+    allowed_origins: Vec<SharedString>,
+    /// Allowed methods.
+    allowed_methods: Vec<SharedString>,
+    /// Allowed request headers.
+    allowed_headers: Vec<SharedString>,
+    /// Response headers exposed to the client.
+    exposed_headers: Vec<SharedString>,
+    /// A flag to determine whether the credentials are allowed.
+    allow_credentials: bool,
+    /// Max age of the preflight result.
+    max_age: Option<Duration>,
+    /// The `origin` header value of the request.
+    request_origin: Option<SharedString>,
+}
+
+impl CorsConfig {
+    /// Creates a new instance which allows any origin.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the allowed origins, such as `https://example.com`.
+    #[inline]
+    pub fn allow_origins<T: Into<SharedString>>(
+        mut self,
+        origins: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.allowed_origins = origins.into_iter().map(|origin| origin.into()).collect();
+        self
+    }
+
+    /// Sets the allowed methods for the `access-control-allow-methods` header.
+    #[inline]
+    pub fn allow_methods<T: Into<SharedString>>(
+        mut self,
+        methods: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.allowed_methods = methods.into_iter().map(|method| method.into()).collect();
+        self
+    }
+
+    /// Sets the allowed request headers for the `access-control-allow-headers` header.
+    #[inline]
+    pub fn allow_headers<T: Into<SharedString>>(
+        mut self,
+        headers: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.allowed_headers = headers.into_iter().map(|header| header.into()).collect();
+        self
+    }
+
+    /// Sets the response headers for the `access-control-expose-headers` header.
+    #[inline]
+    pub fn expose_headers<T: Into<SharedString>>(
+        mut self,
+        headers: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.exposed_headers = headers.into_iter().map(|header| header.into()).collect();
+        self
+    }
+
+    /// Sets whether the credentials are allowed.
+    /// It should be used together with [`allow_origins()`](Self::allow_origins).
+    #[inline]
+    pub fn allow_credentials(mut self, allow_credentials: bool) -> Self {
+        self.allow_credentials = allow_credentials;
+        self
+    }
+
+    /// Sets the max age of the preflight result for the `access-control-max-age` header.
+    #[inline]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets the `origin` header value of the request, which is reflected if it is allowed.
+    #[inline]
+    pub fn request_origin(mut self, origin: impl Into<SharedString>) -> Self {
+        self.request_origin = Some(origin.into());
+        self
+    }
+
+    /// Returns the value of the `access-control-allow-origin` header.
+    /// Returns `None` if the request origin is not allowed.
+    fn allow_origin(&self) -> Option<SharedString> {
+        let request_origin = self
+            .request_origin
+            .as_ref()
+            .map(|origin| origin.trim_end_matches('/'))
+            .filter(|origin| !origin.is_empty());
+        if self.allowed_origins.is_empty() {
+            if self.allow_credentials {
+                None
+            } else {
+                Some("*".into())
+            }
+        } else {
+            request_origin
+                .filter(|origin| {
+                    self.allowed_origins.iter().any(|allowed_origin| {
+                        allowed_origin
+                            .trim_end_matches('/')
+                            .eq_ignore_ascii_case(origin)
+                    })
+                })
+                .map(|origin| origin.to_owned().into())
+        }
+    }
+
+    /// Returns the CORS headers to be emitted, and a flag to determine whether
+    /// the `origin` should be added to the `vary` header.
+    pub(super) fn headers(&self) -> (Vec<(&'static str, String)>, bool) {
+        let mut headers = Vec::with_capacity(6);
+        let vary_origin = !self.allowed_origins.is_empty() || self.allow_credentials;
+        let Some(allow_origin) = self.allow_origin() else {
+            return (headers, vary_origin);
+        };
+        headers.push(("access-control-allow-origin", allow_origin.into_owned()));
+        if !self.allowed_methods.is_empty() {
+            headers.push((
+                "access-control-allow-methods",
+                self.allowed_methods.join(", "),
+            ));
+        }
+        if !self.allowed_headers.is_empty() {
+            headers.push((
+                "access-control-allow-headers",
+                self.allowed_headers.join(", "),
+            ));
+        }
+        if !self.exposed_headers.is_empty() {
+            headers.push((
+                "access-control-expose-headers",
+                self.exposed_headers.join(", "),
+            ));
+        }
+        if self.allow_credentials {
+            headers.push(("access-control-allow-credentials", "true".to_owned()));
+        }
+        if let Some(max_age) = self.max_age {
+            headers.push(("access-control-max-age", max_age.as_secs().to_string()));
+        }
+        (headers, vary_origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CorsConfig;
+
+    #[test]
+    fn it_resolves_cors_allow_origin() {
+        let (headers, vary_origin) = CorsConfig::new().headers();
+        assert_eq!(headers, [("access-control-allow-origin", "*".to_owned())]);
+        assert!(!vary_origin);
+
+        let cors = CorsConfig::new()
+            .allow_credentials(true)
+            .request_origin("https://example.com");
+        let (headers, vary_origin) = cors.headers();
+        assert!(headers.is_empty());
+        assert!(vary_origin);
+
+        let cors = CorsConfig::new()
+            .allow_origins(["https://example.com/"])
+            .allow_credentials(true)
+            .request_origin("https://example.com");
+        let (headers, vary_origin) = cors.headers();
+        assert_eq!(headers[0].1, "https://example.com");
+        assert_eq!(headers[1].0, "access-control-allow-credentials");
+        assert!(vary_origin);
+
+        let cors = CorsConfig::new()
+            .allow_origins(["https://example.com"])
+            .allow_credentials(true);
+        assert!(cors
+            .clone()
+            .request_origin("https://evil.com")
+            .headers()
+            .0
+            .is_empty());
+        assert!(cors.headers().0.is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{extension::JsonObjectExt, JsonValue, Map, SharedString};
+use csv::{ByteRecord, WriterBuilder};
+use std::io::Write;
+
+/// Options for the CSV response.
+///
+/// By default, the fields are delimited by `,` and quoted by `"`,
+/// and the header row is written with the keys of the first object.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Field delimiter.
+    delimiter: u8,
+    /// Quote character.
+    quote: u8,
+    /// A flag to determine whether the header row is written.
+    has_headers: bool,
+    /// Columns which order and filter the fields.
+    columns: Vec<SharedString>,
+}
+
+impl CsvOptions {
+    /// Creates a new instance with the default options.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the field delimiter, such as `;` for the European locales.
+    #[inline]
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets the quote character.
+    #[inline]
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Sets whether the header row is written.
+    #[inline]
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Sets the columns which both order and filter the fields.
+    /// An empty cell is written if a row does not have the column.
+    #[inline]
+    pub fn columns<T: Into<SharedString>>(mut self, columns: impl IntoIterator<Item = T>) -> Self {
+        self.columns = columns.into_iter().map(|col| col.into()).collect();
+        self
+    }
+
+    /// Serializes the JSON value as CSV bytes into the writer.
+    pub(super) fn to_csv<W: Write>(&self, value: &JsonValue, writer: W) -> Result<W, csv::Error> {
+        let rows = match value {
+            JsonValue::Array(vec) => vec.iter().filter_map(|v| v.as_object()).collect(),
+            JsonValue::Object(map) => vec![map],
+            _ => Vec::new(),
+        };
+        let columns = if self.columns.is_empty() {
+            rows.first()
+                .map(|map| map.keys().map(|key| key.as_str()).collect())
+                .unwrap_or_default()
+        } else {
+            self.columns
+                .iter()
+                .map(|col| col.as_ref())
+                .collect::<Vec<_>>()
+        };
+
+        let mut wtr = WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .from_writer(writer);
+        if self.has_headers {
+            wtr.write_record(&columns)?;
+        }
+
+        let num_fields = columns.len();
+        let buffer_size = num_fields * 8;
+        for map in rows {
+            wtr.write_byte_record(&Self::format_record(map, &columns, buffer_size))?;
+        }
+        wtr.flush()?;
+        wtr.into_inner().map_err(|err| err.into_error().into())
+    }
+
+    /// Formats the row as a record with the columns.
+    fn format_record(map: &Map, columns: &[&str], buffer_size: usize) -> ByteRecord {
+        let mut record = ByteRecord::with_capacity(buffer_size, columns.len());
+        for col in columns {
+            let value = map.parse_string(col).unwrap_or_default();
+            record.push_field(value.as_bytes());
+        }
+        record
+    }
+}
+
+impl Default for CsvOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            has_headers: true,
+            columns: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CsvOptions;
+
+    #[test]
+    fn it_serializes_csv_with_options() {
+        let value = serde_json::json!([
+            { "name": "a;b", "amount": 1.5, "id": 1 },
+            { "id": 2, "name": "c" },
+        ]);
+        let options = CsvOptions::new().delimiter(b';').columns(["id", "amount"]);
+        let csv = String::from_utf8(options.to_csv(&value, Vec::new()).unwrap()).unwrap();
+        assert_eq!(csv, "id;amount\n1;1.5\n2;\n");
+
+        let options = CsvOptions::new()
+            .delimiter(b';')
+            .has_headers(false)
+            .columns(["name"]);
+        let csv = String::from_utf8(options.to_csv(&value, Vec::new()).unwrap()).unwrap();
+        assert_eq!(csv, "\"a;b\"\nc\n");
+    }
+}
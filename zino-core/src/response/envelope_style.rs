@@ -0,0 +1,64 @@
+use crate::{extension::TomlTableExt, state::State, LazyLock};
+
+/// Envelope styles for the successful JSON responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EnvelopeStyle {
+    /// The data is wrapped in an envelope with the `success`, `message` and `request_id` fields.
+    /// This is the default value.
+    #[default]
+    Wrapped,
+    /// The bare data is serialized at the top level.
+    Bare,
+}
+
+impl EnvelopeStyle {
+    /// Returns the default envelope style configured by `response.envelope`.
+    #[inline]
+    pub fn global() -> Self {
+        *DEFAULT_ENVELOPE_STYLE
+    }
+}
+
+/// Default envelope style.
+static DEFAULT_ENVELOPE_STYLE: LazyLock<EnvelopeStyle> = LazyLock::new(|| {
+    let envelope = State::shared()
+        .get_config("response")
+        .and_then(|config| config.get_str("envelope"));
+    match envelope {
+        Some("bare") => EnvelopeStyle::Bare,
+        Some("wrapped") | None => EnvelopeStyle::Wrapped,
+        Some(envelope) => {
+            tracing::warn!("unsupported envelope style `{envelope}`");
+            EnvelopeStyle::Wrapped
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::EnvelopeStyle;
+    use crate::response::{Page, Response, StatusCode};
+
+    #[test]
+    fn it_serializes_bare_data() {
+        let mut res = Response::new(StatusCode::OK);
+        res.set_envelope(EnvelopeStyle::Bare);
+        assert_eq!(res.read_bytes().unwrap(), "{}");
+
+        let mut res = Response::new(StatusCode::OK);
+        res.set_envelope(EnvelopeStyle::Bare);
+        res.set_page(&(), Page::new(0, 10, 1));
+        assert_eq!(res.read_bytes().unwrap(), "[]");
+
+        let mut res = Response::new(StatusCode::OK);
+        res.set_envelope(EnvelopeStyle::Bare);
+        res.set_page(&["a", "b"], Page::new(12, 2, 3));
+        assert_eq!(res.read_bytes().unwrap(), r#"["a","b"]"#);
+        let headers = res.encoded_headers();
+        assert!(headers.contains(&("x-total-count", "12".to_owned())));
+        assert!(headers.contains(&("x-page-size", "2".to_owned())));
+        assert!(headers.contains(&("x-current-page", "3".to_owned())));
+        assert!(headers.contains(&("x-total-pages", "6".to_owned())));
+    }
+}
@@ -0,0 +1,67 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// Characters to be percent-encoded in the `grpc-message` trailer.
+const GRPC_MESSAGE_ENCODE_SET: &AsciiSet = &CONTROLS.add(b'%');
+
+/// Maps the HTTP status code to the gRPC status code.
+pub(super) fn grpc_status(status_code: u16) -> u8 {
+    match status_code {
+        200..=299 => 0,
+        400 | 422 => 3,
+        401 => 16,
+        403 => 7,
+        404 => 5,
+        408 | 504 => 4,
+        409 => 10,
+        412 => 9,
+        429 => 8,
+        499 => 1,
+        501 => 12,
+        502 | 503 => 14,
+        500 | 505..=599 => 13,
+        _ => 2,
+    }
+}
+
+/// Encodes the message and the trailers as gRPC-Web frames.
+///
+/// Each frame is prefixed with a flag byte and a 4-byte big-endian length,
+/// where the flag `0x00` is for a data frame and `0x80` is for a trailer frame.
+pub(super) fn encode_frames(message: Option<&[u8]>, status: u8, status_message: &str) -> Bytes {
+    let mut trailers = format!("grpc-status: {status}\r\n");
+    if !status_message.is_empty() {
+        let status_message = utf8_percent_encode(status_message, GRPC_MESSAGE_ENCODE_SET);
+        trailers.push_str(&format!("grpc-message: {status_message}\r\n"));
+    }
+
+    let message_len = message.map(|m| m.len() + 5).unwrap_or_default();
+    let mut buf = BytesMut::with_capacity(message_len + trailers.len() + 5);
+    if let Some(message) = message {
+        buf.put_u8(0x00);
+        buf.put_u32(message.len().try_into().unwrap_or(u32::MAX));
+        buf.put_slice(message);
+    }
+    buf.put_u8(0x80);
+    buf.put_u32(trailers.len().try_into().unwrap_or(u32::MAX));
+    buf.put_slice(trailers.as_bytes());
+    buf.freeze()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_frames;
+
+    #[test]
+    fn it_encodes_grpc_web_frames() {
+        let frames = encode_frames(Some(b"hello"), 0, "");
+        assert_eq!(&frames[..5], &[0x00, 0, 0, 0, 5]);
+        assert_eq!(&frames[5..10], b"hello");
+        assert_eq!(&frames[10..15], &[0x80, 0, 0, 0, 16]);
+        assert_eq!(&frames[15..], b"grpc-status: 0\r\n");
+
+        let frames = encode_frames(None, 5, "not found\r\n");
+        assert_eq!(frames[0], 0x80);
+        assert!(frames.ends_with(b"grpc-message: not found%0D%0A\r\n"));
+    }
+}
@@ -1,33 +1,74 @@
 //! Constructing responses and rejections.
 
 use crate::{
+    bail,
+    datetime::DateTime,
     error::Error,
-    extension::JsonValueExt,
+    extension::{JsonObjectExt, JsonValueExt},
     file::NamedFile,
     helper,
     request::RequestContext,
-    trace::{ServerTiming, TimingMetric, TraceContext},
+    trace::{ServerTiming, TimingBuffer, TimingMetric, TimingSpan, TraceContext},
     validation::Validation,
-    JsonValue, SharedString, Uuid,
+    JsonValue, Map, SharedString, Uuid,
 };
+use body_limit::LimitedWriter;
 use bytes::Bytes;
 use etag::EntityTag;
+use futures::{future, stream, AsyncRead, AsyncReadExt, Stream, StreamExt, TryStreamExt};
 use serde::Serialize;
 use smallvec::SmallVec;
 use std::{
+    io::Write,
     marker::PhantomData,
+    mem,
+    sync::Arc,
     time::{Duration, Instant},
 };
+use stream_body::StreamBody;
+use url::Url;
 
 #[cfg(feature = "cookie")]
-use cookie::Cookie;
-
+use cookie::{Cookie, SameSite};
+
+mod body_limit;
+mod case_style;
+mod compression;
+mod conditional;
+mod content_digest;
+mod content_disposition;
+mod cors_config;
+mod csv_options;
+mod envelope_style;
+mod grpc_web;
+mod negotiation;
+mod page;
+mod redirect_kind;
 mod rejection;
 mod response_code;
+mod retry_after;
+mod security_headers;
+mod sse;
+mod stream_body;
+mod toml_format;
+mod transform_context;
 mod webhook;
-
+mod xml;
+
+pub use case_style::CaseStyle;
+pub use compression::CompressionAlgo;
+pub use content_digest::DigestAlgorithm;
+pub use cors_config::CorsConfig;
+pub use csv_options::CsvOptions;
+pub use envelope_style::EnvelopeStyle;
+pub use page::Page;
+pub use redirect_kind::RedirectKind;
 pub use rejection::{ExtractRejection, Rejection};
 pub use response_code::ResponseCode;
+pub use retry_after::RetryAfter;
+pub use security_headers::SecurityHeaders;
+pub use stream_body::{BodyStream, Trailers};
+pub use transform_context::TransformContext;
 pub use webhook::WebHook;
 
 /// An HTTP status code for http v0.2.
@@ -41,6 +82,22 @@ pub type StatusCode = http::StatusCode;
 /// A function pointer of transforming the response data.
 pub type DataTransformer = fn(data: &JsonValue) -> Result<Bytes, Error>;
 
+/// A function pointer of transforming the response data with the context.
+pub type ContextualDataTransformer =
+    fn(data: &JsonValue, ctx: &TransformContext<'_>) -> Result<Bytes, Error>;
+
+/// A function pointer of transforming the encoded bytes of the response data.
+pub type BytesTransformer = fn(bytes: Bytes) -> Result<Bytes, Error>;
+
+/// A transformer of the response data.
+#[derive(Debug, Clone, Copy)]
+enum Transformer {
+    /// A simple transformer.
+    Simple(DataTransformer),
+    /// A transformer with the context.
+    Contextual(ContextualDataTransformer),
+}
+
 /// An HTTP response.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -55,6 +112,9 @@ pub struct Response<S: ResponseCode> {
     /// Status code.
     #[serde(rename = "status")]
     status_code: u16,
+    /// Custom reason phrase.
+    #[serde(skip)]
+    reason_phrase: Option<&'static str>,
     /// Error code.
     #[serde(rename = "error")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -74,6 +134,12 @@ pub struct Response<S: ResponseCode> {
     /// A context-specific descriptive message for successful response.
     #[serde(skip_serializing_if = "Option::is_none")]
     message: Option<SharedString>,
+    /// A list of invalid params with the reasons and error codes.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    invalid_params: Vec<Map>,
+    /// Extension members of the problem details.
+    #[serde(flatten)]
+    extensions: Map,
     /// Start time.
     #[serde(skip)]
     start_time: Instant,
@@ -84,21 +150,87 @@ pub struct Response<S: ResponseCode> {
     #[serde(rename = "data")]
     #[serde(skip_serializing_if = "JsonValue::is_null")]
     json_data: JsonValue,
+    /// Pagination metadata for the list data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pagination: Option<Page>,
     /// Bytes data.
     #[serde(skip)]
     bytes_data: Bytes,
+    /// Encoded response body and the headers derived from it, cached by `read_bytes()`.
+    #[serde(skip)]
+    encoded_body: Option<(Bytes, Vec<(&'static str, String)>)>,
+    /// Streaming body.
+    #[serde(skip)]
+    body_stream: Option<StreamBody>,
+    /// Trailers sent after the streaming body.
+    #[serde(skip)]
+    trailers: Trailers,
+    /// Case style for the keys of the response data.
+    #[serde(skip)]
+    case_style: Option<CaseStyle>,
+    /// Envelope style for the successful JSON response.
+    #[serde(skip)]
+    envelope_style: EnvelopeStyle,
+    /// Root element name for the XML response.
+    #[serde(skip)]
+    xml_root: Option<SharedString>,
+    /// Options for the CSV response.
+    #[serde(skip)]
+    csv_options: Option<CsvOptions>,
+    /// Compression algorithm for the response body.
+    #[serde(skip)]
+    compression: CompressionAlgo,
+    /// Max size of the response body in bytes.
+    #[serde(skip)]
+    max_body_size: Option<usize>,
+    /// Generates the `etag` header from the response body, with a flag for the weak validator.
+    #[serde(skip)]
+    auto_etag: Option<bool>,
+    /// Value of the `retry-after` header.
+    #[serde(skip)]
+    retry_after: Option<RetryAfter>,
     /// Transformer of the response data.
     #[serde(skip)]
-    data_transformer: Option<DataTransformer>,
+    data_transformer: Option<Transformer>,
+    /// Transformers of the encoded bytes, which are run in order.
+    #[serde(skip)]
+    bytes_transformers: Vec<BytesTransformer>,
     /// Content type.
     #[serde(skip)]
     content_type: Option<SharedString>,
+    /// Locale.
+    #[serde(skip)]
+    locale: Option<SharedString>,
+    /// Localization key of the response code.
+    #[serde(skip)]
+    localization_key: Option<SharedString>,
+    /// A flag to determine whether the message or detail is derived from the response code.
+    #[serde(skip)]
+    code_message: bool,
     /// Trace context.
     #[serde(skip)]
     trace_context: Option<TraceContext>,
+    /// An override of the `sampled` flag in the trace context.
+    #[serde(skip)]
+    sampled: Option<bool>,
     /// Server timing.
     #[serde(skip)]
     server_timing: ServerTiming,
+    /// Buffer of the server timing metrics recorded by the spans.
+    #[serde(skip)]
+    timing_buffer: TimingBuffer,
+    /// Algorithms for the `content-digest` header.
+    #[serde(skip)]
+    content_digest: SmallVec<[DigestAlgorithm; 2]>,
+    /// Deprecated response fields with the notes.
+    #[serde(skip)]
+    deprecated_fields: Vec<(SharedString, SharedString)>,
+    /// Security headers for HTML documents.
+    #[serde(skip)]
+    security_headers: Option<SecurityHeaders>,
+    /// CORS configuration.
+    #[serde(skip)]
+    cors: Option<CorsConfig>,
     /// Custom headers.
     #[serde(skip)]
     headers: SmallVec<[(SharedString, String); 8]>,
@@ -115,21 +247,46 @@ impl<S: ResponseCode> Response<S> {
         let mut res = Self {
             type_uri: code.type_uri(),
             title: code.title(),
-            status_code: code.status_code(),
+            status_code: response_code::normalize_status_code(code.status_code()),
+            reason_phrase: code.reason_phrase(),
             error_code: code.error_code(),
             business_code: code.business_code(),
             detail: None,
             instance: None,
             success,
             message: None,
+            invalid_params: Vec::new(),
+            extensions: Map::new(),
             start_time: Instant::now(),
             request_id: Uuid::nil(),
             json_data: JsonValue::Null,
+            pagination: None,
             bytes_data: Bytes::new(),
+            encoded_body: None,
+            body_stream: None,
+            trailers: Trailers::default(),
+            case_style: None,
+            envelope_style: EnvelopeStyle::global(),
+            xml_root: None,
+            csv_options: None,
+            compression: CompressionAlgo::Identity,
+            max_body_size: None,
+            auto_etag: None,
+            retry_after: None,
             data_transformer: None,
+            bytes_transformers: Vec::new(),
             content_type: None,
+            locale: None,
+            localization_key: code.localization_key(),
+            code_message: true,
             trace_context: None,
+            sampled: None,
             server_timing: ServerTiming::new(),
+            timing_buffer: TimingBuffer::default(),
+            content_digest: SmallVec::new(),
+            deprecated_fields: Vec::new(),
+            security_headers: None,
+            cors: None,
             headers: SmallVec::new(),
             phantom: PhantomData,
         };
@@ -148,21 +305,46 @@ impl<S: ResponseCode> Response<S> {
         let mut res = Self {
             type_uri: code.type_uri(),
             title: code.title(),
-            status_code: code.status_code(),
+            status_code: response_code::normalize_status_code(code.status_code()),
+            reason_phrase: code.reason_phrase(),
             error_code: code.error_code(),
             business_code: code.business_code(),
             detail: None,
             instance: (!success).then(|| ctx.instance().into()),
             success,
             message: None,
+            invalid_params: Vec::new(),
+            extensions: Map::new(),
             start_time: ctx.start_time(),
             request_id: ctx.request_id(),
             json_data: JsonValue::Null,
+            pagination: None,
             bytes_data: Bytes::new(),
+            encoded_body: None,
+            body_stream: None,
+            trailers: Trailers::default(),
+            case_style: None,
+            envelope_style: EnvelopeStyle::global(),
+            xml_root: None,
+            csv_options: None,
+            compression: CompressionAlgo::Identity,
+            max_body_size: None,
+            auto_etag: None,
+            retry_after: None,
             data_transformer: None,
+            bytes_transformers: Vec::new(),
             content_type: None,
+            locale: None,
+            localization_key: code.localization_key(),
+            code_message: true,
             trace_context: None,
+            sampled: None,
             server_timing: ServerTiming::new(),
+            timing_buffer: TimingBuffer::default(),
+            content_digest: SmallVec::new(),
+            deprecated_fields: Vec::new(),
+            security_headers: None,
+            cors: None,
             headers: SmallVec::new(),
             phantom: PhantomData,
         };
@@ -171,6 +353,10 @@ impl<S: ResponseCode> Response<S> {
         } else {
             res.detail = message;
         }
+        #[cfg(feature = "i18n")]
+        {
+            res.locale = ctx.locale().map(|locale| locale.to_string().into());
+        }
         res.trace_context = Some(ctx.new_trace_context());
         res
     }
@@ -181,6 +367,10 @@ impl<S: ResponseCode> Response<S> {
         self.start_time = ctx.start_time();
         self.request_id = ctx.request_id();
         self.trace_context = Some(ctx.new_trace_context());
+        #[cfg(feature = "i18n")]
+        {
+            self.locale = ctx.locale().map(|locale| locale.to_string().into());
+        }
         self
     }
 
@@ -202,20 +392,25 @@ impl<S: ResponseCode> Response<S> {
             Ok(content) => {
                 self.json_data = content.into();
                 self.bytes_data = Bytes::new();
+                self.invalidate_body();
                 self.content_type = Some("text/html; charset=utf-8".into());
             }
             Err(err) => {
                 let code = S::INTERNAL_SERVER_ERROR;
                 self.type_uri = code.type_uri();
                 self.title = code.title();
-                self.status_code = code.status_code();
+                self.status_code = response_code::normalize_status_code(code.status_code());
+                self.reason_phrase = code.reason_phrase();
                 self.error_code = code.error_code();
                 self.business_code = code.business_code();
                 self.success = false;
                 self.detail = Some(err.to_string().into());
                 self.message = None;
+                self.localization_key = code.localization_key();
+                self.code_message = false;
                 self.json_data = JsonValue::Null;
                 self.bytes_data = Bytes::new();
+                self.invalidate_body();
             }
         }
         self
@@ -227,10 +422,13 @@ impl<S: ResponseCode> Response<S> {
         let message = code.message();
         self.type_uri = code.type_uri();
         self.title = code.title();
-        self.status_code = code.status_code();
+        self.status_code = response_code::normalize_status_code(code.status_code());
+        self.reason_phrase = code.reason_phrase();
         self.error_code = code.error_code();
         self.business_code = code.business_code();
         self.success = success;
+        self.localization_key = code.localization_key();
+        self.code_message = true;
         if success {
             self.detail = None;
             self.message = message;
@@ -238,36 +436,53 @@ impl<S: ResponseCode> Response<S> {
             self.detail = message;
             self.message = None;
         }
+        self.invalidate_body();
     }
 
-    /// Sets the status code.
+    /// Sets the status code. An invalid code which is not in the range `100..600`
+    /// is replaced by `500`, and the custom reason phrase is cleared.
     #[inline]
     pub fn set_status_code(&mut self, status_code: impl Into<u16>) {
-        self.status_code = status_code.into();
+        self.status_code = response_code::normalize_status_code(status_code.into());
+        self.reason_phrase = None;
+        self.invalidate_body();
+    }
+
+    /// Sets a custom reason phrase in the status line, such as `Partial Success` for `299`.
+    ///
+    /// It is only emitted for HTTP/1 where the framework permits,
+    /// and it is ignored if the phrase contains invalid characters.
+    #[inline]
+    pub fn set_reason_phrase(&mut self, reason: &'static str) {
+        self.reason_phrase = Some(reason);
     }
 
     /// Sets the error code.
     #[inline]
     pub fn set_error_code(&mut self, error_code: impl Into<S::ErrorCode>) {
         self.error_code = Some(error_code.into());
+        self.invalidate_body();
     }
 
     /// Sets the bussiness code.
     #[inline]
     pub fn set_business_code(&mut self, business_code: impl Into<S::BusinessCode>) {
         self.business_code = Some(business_code.into());
+        self.invalidate_body();
     }
 
     /// Sets a URI reference that identifies the specific occurrence of the problem.
     #[inline]
     pub fn set_instance(&mut self, instance: impl Into<SharedString>) {
         self.instance = Some(instance.into());
+        self.invalidate_body();
     }
 
     /// Sets the message. If the response is not successful,
     /// it should be a human-readable explanation specific to this occurrence of the problem.
     pub fn set_message(&mut self, message: impl Into<SharedString>) {
         fn inner<S: ResponseCode>(res: &mut Response<S>, message: SharedString) {
+            res.code_message = false;
             if res.is_success() {
                 res.detail = None;
                 res.message = Some(message);
@@ -275,6 +490,7 @@ impl<S: ResponseCode> Response<S> {
                 res.detail = Some(message);
                 res.message = None;
             }
+            res.invalidate_body();
         }
         inner::<S>(self, message.into())
     }
@@ -283,6 +499,7 @@ impl<S: ResponseCode> Response<S> {
     pub fn set_error_message(&mut self, error: impl Into<Error>) {
         fn inner<S: ResponseCode>(res: &mut Response<S>, error: Error) {
             let message = error.to_string().into();
+            res.code_message = false;
             if res.is_success() {
                 res.detail = None;
                 res.message = Some(message);
@@ -290,10 +507,33 @@ impl<S: ResponseCode> Response<S> {
                 res.detail = Some(message);
                 res.message = None;
             }
+            res.invalidate_body();
         }
         inner::<S>(self, error.into())
     }
 
+    /// Sets the error message with the source chain.
+    ///
+    /// The terse message is set in the same way as [`set_error_message()`](Self::set_error_message),
+    /// while the messages of the source errors are exposed as the `causes` extension member
+    /// for an `application/problem+json` response. Since the error messages may contain
+    /// sensitive data, it should only be used in development.
+    pub fn set_error_message_verbose(&mut self, error: impl Into<Error>) {
+        let error = error.into();
+        let causes = error
+            .sources()
+            .skip(1)
+            .map(|err| err.message().to_owned())
+            .collect::<Vec<_>>();
+        self.set_error_message(error);
+        if causes.is_empty() {
+            self.extensions.remove("causes");
+        } else {
+            self.extensions.upsert("causes", causes);
+        }
+        self.invalidate_body();
+    }
+
     /// Sets the response data.
     #[inline]
     pub fn set_data<T: Serialize>(&mut self, data: &T) {
@@ -301,16 +541,36 @@ impl<S: ResponseCode> Response<S> {
             Ok(value) => {
                 self.json_data = value;
                 self.bytes_data = Bytes::new();
+                self.invalidate_body();
             }
             Err(err) => self.set_error_message(err),
         }
     }
 
+    /// Sets the list items as the response data with the pagination metadata.
+    ///
+    /// The metadata is serialized as the `pagination` field alongside the `data` field.
+    /// If the link URL of the page has been set, the `link` header is appended
+    /// with `rel="next"` and `rel="prev"` for the adjacent pages.
+    pub fn set_page<T: Serialize>(&mut self, items: &T, page: Page) {
+        self.set_data(items);
+        for link in page.links() {
+            self.append_link(link);
+        }
+        self.pagination = Some(page);
+    }
+
     /// Sets the JSON data.
+    ///
+    /// If there is no data transformer and the content type is not a JSON type,
+    /// a string value is written as the raw bytes while the others are serialized as JSON.
+    /// Use [`set_text_response()`](Self::set_text_response) or
+    /// [`set_html_response()`](Self::set_html_response) to make the intent explicit.
     #[inline]
     pub fn set_json_data(&mut self, data: impl Into<JsonValue>) {
         self.json_data = data.into();
         self.bytes_data = Bytes::new();
+        self.invalidate_body();
     }
 
     /// Sets the bytes data.
@@ -318,19 +578,181 @@ impl<S: ResponseCode> Response<S> {
     pub fn set_bytes_data(&mut self, data: impl Into<Bytes>) {
         self.json_data = JsonValue::Null;
         self.bytes_data = data.into();
+        self.invalidate_body();
     }
 
     /// Sets the response data for the validation.
     #[inline]
     pub fn set_validation_data(&mut self, validation: Validation) {
+        self.invalid_params = validation.invalid_params_detail();
         self.json_data = validation.into_map().into();
         self.bytes_data = Bytes::new();
+        self.invalidate_body();
+    }
+
+    /// Sets an extension member of the problem details as defined in RFC 7807.
+    ///
+    /// The extension members are serialized as the siblings of the standard fields
+    /// such as `status` and `detail` for an `application/problem+json` response,
+    /// and they are ignored for other content types. The keys of the standard fields
+    /// are reserved and can not be used.
+    pub fn set_problem_extension(&mut self, key: &str, value: impl Into<JsonValue>) {
+        const RESERVED_KEYS: [&str; 12] = [
+            "type",
+            "title",
+            "status",
+            "error",
+            "code",
+            "detail",
+            "instance",
+            "success",
+            "message",
+            "invalid_params",
+            "request_id",
+            "data",
+        ];
+        if RESERVED_KEYS.contains(&key) {
+            tracing::warn!(key, "the problem extension member is reserved");
+        } else {
+            self.extensions.upsert(key, value.into());
+            self.invalidate_body();
+        }
+    }
+
+    /// Sets the case style for the keys of the response data.
+    ///
+    /// The keys of `data` are rewritten recursively when the response is serialized,
+    /// while the envelope fields are kept in `snake_case`. The keys starting with `$`
+    /// are preserved as they are.
+    #[inline]
+    pub fn set_case(&mut self, case: CaseStyle) {
+        self.case_style = Some(case);
+        self.invalidate_body();
+    }
+
+    /// Sets the envelope style for the successful JSON response.
+    ///
+    /// With [`EnvelopeStyle::Bare`], only the `data` is serialized, and the request ID
+    /// and the trace context are available in the headers. An empty data is serialized
+    /// as `[]` for a page and `{}` otherwise. The pagination metadata is emitted
+    /// in the `x-total-count`, `x-page-size`, `x-current-page` and `x-total-pages` headers
+    /// together with the `link` header. The errors are always serialized as the problem details.
+    #[inline]
+    pub fn set_envelope(&mut self, style: EnvelopeStyle) {
+        self.envelope_style = style;
+        self.invalidate_body();
+    }
+
+    /// Sets the compression algorithm for the response body.
+    ///
+    /// The body is compressed in [`read_bytes()`](Self::read_bytes) with the
+    /// `content-encoding` header emitted, unless its size is below the threshold
+    /// configured by `response.compression-threshold`, the content type is not
+    /// compressible, or the `content-encoding` header has already been set.
+    /// The algorithm can be negotiated by [`CompressionAlgo::negotiate()`].
+    #[inline]
+    pub fn set_compression(&mut self, algo: CompressionAlgo) {
+        self.compression = algo;
+        self.invalidate_body();
+    }
+
+    /// Sets the max size of the response body in bytes, which is enforced in
+    /// [`read_bytes()`](Self::read_bytes) by returning an error instead of allocating unbounded.
+    ///
+    /// The JSON data is checked incrementally while being serialized. For a buffered body,
+    /// it defaults to the `response.max-body-size` config with a fallback of 128 MiB.
+    /// A streaming body is only limited if the max size is set explicitly,
+    /// in which case the stream is terminated with an error once the size is exceeded.
+    #[inline]
+    pub fn set_max_body_size(&mut self, bytes: usize) {
+        self.max_body_size = Some(bytes);
+        self.invalidate_body();
+    }
+
+    /// Sets the `retry-after` header for a `429`, `503` or `3xx` response.
+    ///
+    /// The header is emitted in [`finalize()`](Self::finalize), and it is ignored
+    /// with a debug message for a successful response.
+    #[inline]
+    pub fn set_retry_after(&mut self, after: impl Into<RetryAfter>) {
+        self.retry_after = Some(after.into());
     }
 
     /// Sets a transformer for the response data.
+    /// It replaces the transformers pushed by [`push_data_transformer()`](Self::push_data_transformer).
     #[inline]
     pub fn set_data_transformer(&mut self, transformer: DataTransformer) {
-        self.data_transformer = Some(transformer);
+        self.data_transformer = Some(Transformer::Simple(transformer));
+        self.bytes_transformers.clear();
+        self.invalidate_body();
+    }
+
+    /// Sets a transformer for the response data, which has access to the
+    /// [`TransformContext`] such as the content type and the locale.
+    /// It replaces the transformers pushed by [`push_data_transformer()`](Self::push_data_transformer).
+    #[inline]
+    pub fn set_contextual_data_transformer(&mut self, transformer: ContextualDataTransformer) {
+        self.data_transformer = Some(Transformer::Contextual(transformer));
+        self.bytes_transformers.clear();
+        self.invalidate_body();
+    }
+
+    /// Pushes a transformer for the encoded bytes of the response data.
+    ///
+    /// The transformers are run in order after the response data has been encoded
+    /// by the data transformer or the built-in encoder for the content type,
+    /// where each one receives the output of the previous one.
+    /// It can be used to encrypt or sign the encoded bytes.
+    #[inline]
+    pub fn push_data_transformer(&mut self, transformer: BytesTransformer) {
+        self.bytes_transformers.push(transformer);
+        self.invalidate_body();
+    }
+
+    /// Sets the locale.
+    #[inline]
+    pub fn set_locale(&mut self, locale: impl Into<SharedString>) {
+        self.locale = Some(locale.into());
+        self.invalidate_body();
+    }
+
+    /// Localizes the title and the message derived from the response code
+    /// with the locale negotiated by the `accept-language` header value.
+    ///
+    /// The translations are looked up as `{key}-title` and `{key}-message`
+    /// for the [localization key](ResponseCode::localization_key), falling back to
+    /// the default locale. The messages set via [`set_message()`](Self::set_message) or
+    /// [`set_error_message()`](Self::set_error_message) are left untouched.
+    #[cfg(feature = "i18n")]
+    pub fn localize(&mut self, accept_language: &str) {
+        use crate::i18n;
+        use unic_langid::LanguageIdentifier;
+
+        let locale = i18n::select_language(accept_language, &i18n::SUPPORTED_LOCALES)
+            .unwrap_or(&i18n::DEFAULT_LOCALE)
+            .to_owned();
+        let Ok(lang_id) = locale.parse::<LanguageIdentifier>() else {
+            return;
+        };
+        self.locale = Some(locale.into());
+
+        let Some(key) = self.localization_key.clone() else {
+            return;
+        };
+        if self.title.is_some() {
+            if let Ok(title) = i18n::translate(&lang_id, &format!("{key}-title"), None) {
+                self.title = Some(title);
+            }
+        }
+        if self.code_message && (self.message.is_some() || self.detail.is_some()) {
+            if let Ok(message) = i18n::translate(&lang_id, &format!("{key}-message"), None) {
+                if self.is_success() {
+                    self.message = Some(message);
+                } else {
+                    self.detail = Some(message);
+                }
+            }
+        }
     }
 
     /// Sets the content type.
@@ -339,6 +761,8 @@ impl<S: ResponseCode> Response<S> {
     ///
     /// Currently, we have built-in support for the following values:
     ///
+    /// - `application/grpc-web+json`
+    /// - `application/grpc-web+proto`
     /// - `application/json`
     /// - `application/jsonlines`
     /// - `application/octet-stream`
@@ -350,6 +774,34 @@ impl<S: ResponseCode> Response<S> {
     #[inline]
     pub fn set_content_type(&mut self, content_type: impl Into<SharedString>) {
         self.content_type = Some(content_type.into());
+        self.invalidate_body();
+    }
+
+    /// Negotiates the content type with the `accept` header value and sets the data
+    /// as the response body with the matching built-in transformer.
+    ///
+    /// The supported content types are `application/json`, `application/jsonlines`,
+    /// `text/csv` and `application/x-www-form-urlencoded`. The `q` weights and
+    /// the wildcards `*/*` and `type/*` are respected. If nothing matches,
+    /// it falls back to `application/json`.
+    pub fn negotiate(&mut self, accept: &str, data: impl Into<JsonValue>) {
+        const SUPPORTED_TYPES: [&str; 4] = [
+            "application/json",
+            "application/jsonlines",
+            "text/csv",
+            "application/x-www-form-urlencoded",
+        ];
+        let data = data.into();
+        match negotiation::negotiate_media_type(accept, &SUPPORTED_TYPES) {
+            Some("application/jsonlines") => self.set_jsonlines_response(data),
+            Some("text/csv") => self.set_csv_response(data),
+            Some("application/x-www-form-urlencoded") => self.set_form_response(data),
+            _ => {
+                self.set_json_response(data);
+                self.set_content_type("application/json; charset=utf-8");
+            }
+        }
+        self.append_vary("accept");
     }
 
     /// Sets the form data as the response body.
@@ -388,6 +840,138 @@ impl<S: ResponseCode> Response<S> {
         inner::<S>(self, data.into())
     }
 
+    /// Sets a stream of JSON Lines as the response body.
+    ///
+    /// Each item is serialized as a newline-terminated line and sent as a separate chunk.
+    /// If an item fails to be serialized, the stream is terminated with a final line
+    /// `{"error": "..."}` instead of being truncated silently.
+    pub fn set_jsonlines_stream<T: Serialize>(
+        &mut self,
+        stream: impl Stream<Item = T> + Send + 'static,
+    ) {
+        let stream = stream.scan(false, |terminated, item| {
+            let chunk = (!*terminated).then(|| {
+                let mut line = serde_json::to_vec(&item).unwrap_or_else(|err| {
+                    *terminated = true;
+                    let message = serde_json::json!({ "error": err.to_string() });
+                    message.to_string().into_bytes()
+                });
+                line.push(b'\n');
+                Ok(Bytes::from(line))
+            });
+            future::ready(chunk)
+        });
+        self.json_data = JsonValue::Null;
+        self.bytes_data = Bytes::new();
+        self.invalidate_body();
+        self.body_stream = Some(StreamBody::new(stream.boxed()));
+        self.set_content_type("application/jsonlines; charset=utf-8");
+    }
+
+    /// Sets an iterator of JSON Lines as the response body without buffering the whole data.
+    ///
+    /// The items are produced lazily when the body is polled, and each of them is sent
+    /// as a separate chunk in the same way as [`set_jsonlines_stream()`](Self::set_jsonlines_stream).
+    #[inline]
+    pub fn set_jsonlines_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+        I::IntoIter: Send + 'static,
+    {
+        self.set_jsonlines_stream(stream::iter(iter));
+    }
+
+    /// Sets a stream of server-sent events as the response body.
+    ///
+    /// Each item is formatted as an event of `data: <json>`, where the reserved keys
+    /// `$id`, `$event` and `$retry` of an object are emitted as the corresponding fields
+    /// and `$data` overrides the event data. If the `keep_alive` interval is specified,
+    /// a comment line `:` is sent when there is no event in the interval.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use futures::channel::mpsc;
+    /// use std::time::Duration;
+    ///
+    /// let (tx, rx) = mpsc::unbounded();
+    /// tx.unbounded_send(json!({ "$event": "update", "count": 1 }))?;
+    ///
+    /// let mut res = Response::default().context(&req);
+    /// res.set_sse_response(rx, Some(Duration::from_secs(15)));
+    /// ```
+    pub fn set_sse_response(
+        &mut self,
+        stream: impl Stream<Item = JsonValue> + Send + 'static,
+        keep_alive: Option<Duration>,
+    ) {
+        let events = stream
+            .map(|item| Bytes::from(sse::format_event(item)))
+            .boxed();
+        let events = if let Some(interval) = keep_alive {
+            sse::keep_alive(events, interval)
+        } else {
+            events
+        };
+        self.json_data = JsonValue::Null;
+        self.bytes_data = Bytes::new();
+        self.invalidate_body();
+        self.body_stream = Some(StreamBody::new(events.map(Ok).boxed()));
+        self.set_content_type("text/event-stream");
+        self.remove_header("cache-control");
+        self.insert_header("cache-control", "no-cache");
+    }
+
+    /// Sets a stream of bytes as the response body with the content type,
+    /// which is sent without being buffered, transformed or compressed.
+    ///
+    /// The `content-length` header is emitted if the length is known,
+    /// otherwise the body is sent with the chunked transfer encoding.
+    /// The headers in [`finalize()`](Self::finalize) are still emitted, but the `total` metric
+    /// of the `server-timing` header does not include the time of streaming.
+    pub fn set_stream_body<E: Into<Error> + 'static>(
+        &mut self,
+        stream: impl Stream<Item = Result<Bytes, E>> + Send + 'static,
+        content_type: impl Into<SharedString>,
+        content_length: Option<u64>,
+    ) {
+        self.json_data = JsonValue::Null;
+        self.bytes_data = Bytes::new();
+        self.invalidate_body();
+        self.body_stream = Some(StreamBody::new(stream.map_err(Into::into).boxed()));
+        self.set_content_type(content_type);
+        self.remove_header("content-length");
+        if let Some(content_length) = content_length {
+            self.insert_header("content-length", content_length);
+        }
+    }
+
+    /// Sets an async reader as the streaming response body with the content type.
+    /// The reader is read in chunks of 8 KiB, and the stream is terminated after an error.
+    ///
+    /// See [`set_stream_body()`](Self::set_stream_body) for the details.
+    pub fn set_reader_body(
+        &mut self,
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        content_type: impl Into<SharedString>,
+        content_length: Option<u64>,
+    ) {
+        let state = (Some(reader), vec![0; 8192]);
+        let stream = stream::unfold(state, |(reader, mut buf)| async move {
+            let mut reader = reader?;
+            match reader.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(size) => {
+                    let chunk = Bytes::copy_from_slice(&buf[..size]);
+                    Some((Ok(chunk), (Some(reader), buf)))
+                }
+                Err(err) => Some((Err(Error::from(err)), (None, buf))),
+            }
+        });
+        self.set_stream_body(stream, content_type, content_length);
+    }
+
     /// Sets the CSV data as the response body.
     #[inline]
     pub fn set_csv_response(&mut self, data: impl Into<JsonValue>) {
@@ -399,11 +983,92 @@ impl<S: ResponseCode> Response<S> {
         inner::<S>(self, data.into())
     }
 
-    /// Sets the plain text as the response body.
+    /// Sets the CSV data as the response body with the options,
+    /// such as the delimiter, the quote character and the columns.
+    #[inline]
+    pub fn set_csv_response_with(&mut self, data: impl Into<JsonValue>, options: CsvOptions) {
+        self.set_json_data(data);
+        self.set_content_type("text/csv; charset=utf-8");
+        self.data_transformer = None;
+        self.csv_options = Some(options);
+        self.invalidate_body();
+    }
+
+    /// Sets the `content-disposition` header to make the response body downloaded
+    /// as an attachment with the filename.
+    ///
+    /// Non-ASCII filenames are encoded in the `filename*` parameter as defined in RFC 5987.
+    /// The content type of the response is not changed.
+    #[inline]
+    pub fn set_attachment(&mut self, filename: &str) {
+        self.set_content_disposition("attachment", filename);
+    }
+
+    /// Sets the `content-disposition` header to make the response body displayed
+    /// inline with the filename.
+    #[inline]
+    pub fn set_inline(&mut self, filename: &str) {
+        self.set_content_disposition("inline", filename);
+    }
+
+    /// Sets the `content-disposition` header with the disposition type and filename.
+    fn set_content_disposition(&mut self, disposition: &str, filename: &str) {
+        let value = content_disposition::format_content_disposition(disposition, filename);
+        self.remove_header("content-disposition");
+        self.insert_header("content-disposition", value);
+    }
+
+    /// Sets the XML data as the response body.
+    ///
+    /// Object keys become elements and arrays become repeated elements.
+    /// The root element is `<root>` by default, which can be changed by
+    /// [`set_xml_root()`](Self::set_xml_root).
+    #[cfg(feature = "xml")]
+    #[inline]
+    pub fn set_xml_response(&mut self, data: impl Into<JsonValue>) {
+        self.set_json_data(data);
+        self.set_content_type("application/xml; charset=utf-8");
+        self.data_transformer = None;
+    }
+
+    /// Sets the root element name for the XML response.
+    #[cfg(feature = "xml")]
+    #[inline]
+    pub fn set_xml_root(&mut self, root: impl Into<SharedString>) {
+        self.xml_root = Some(root.into());
+        self.invalidate_body();
+    }
+
+    /// Sets the TOML data as the response body.
+    ///
+    /// The data should be an object, and the `null` fields are omitted.
+    #[cfg(feature = "toml")]
+    #[inline]
+    pub fn set_toml_response(&mut self, data: impl Into<JsonValue>) {
+        fn inner<S: ResponseCode>(res: &mut Response<S>, data: JsonValue) {
+            res.set_json_data(data);
+            res.set_content_type("application/toml; charset=utf-8");
+            res.set_data_transformer(|data| Ok(toml_format::to_toml(data)?.into()));
+        }
+        inner::<S>(self, data.into())
+    }
+
+    /// Sets the plain text as the response body with the content type `text/plain; charset=utf-8`.
+    /// The text is written as is without being quoted as a JSON string.
     #[inline]
     pub fn set_text_response(&mut self, data: impl Into<String>) {
         self.set_json_data(data.into());
         self.set_content_type("text/plain; charset=utf-8");
+        self.set_data_transformer(|data| Ok(data.as_str().unwrap_or_default().to_owned().into()));
+    }
+
+    /// Sets the raw HTML as the response body with the content type `text/html; charset=utf-8`.
+    /// The HTML is written as is without being escaped.
+    #[inline]
+    pub fn set_html_response(&mut self, data: impl Into<String>) {
+        self.set_json_data(data.into());
+        self.set_content_type("text/html; charset=utf-8");
+        self.set_data_transformer(|data| Ok(data.as_str().unwrap_or_default().to_owned().into()));
     }
 
     /// Sets the bytes data as the response body.
@@ -413,10 +1078,44 @@ impl<S: ResponseCode> Response<S> {
         self.set_content_type("application/octet-stream");
     }
 
+    /// Sets the JSON data as a gRPC-Web response body with the content type
+    /// `application/grpc-web+json`.
+    ///
+    /// The message is framed with the 5-byte length prefix and followed by a trailer frame
+    /// carrying `grpc-status` and `grpc-message`, which are mapped from the HTTP status code.
+    /// The HTTP status code is always `200 OK` for the gRPC-Web response.
+    #[inline]
+    pub fn set_grpc_web_response(&mut self, data: impl Into<JsonValue>) {
+        fn inner<S: ResponseCode>(res: &mut Response<S>, data: JsonValue) {
+            res.set_json_data(data);
+            res.set_content_type("application/grpc-web+json");
+            res.set_data_transformer(|data| Ok(serde_json::to_vec(&data)?.into()));
+        }
+        inner::<S>(self, data.into())
+    }
+
+    /// Sets the protobuf-encoded message as a gRPC-Web response body with the content type
+    /// `application/grpc-web+proto`.
+    ///
+    /// See [`set_grpc_web_response()`](Self::set_grpc_web_response) for the framing.
+    #[inline]
+    pub fn set_grpc_web_message(&mut self, message: impl Into<Bytes>) {
+        self.set_bytes_data(message);
+        self.set_content_type("application/grpc-web+proto");
+    }
+
     /// Sets the request ID.
     #[inline]
     pub(crate) fn set_request_id(&mut self, request_id: Uuid) {
         self.request_id = request_id;
+        self.invalidate_body();
+    }
+
+    /// Overrides the `sampled` flag of the `traceparent` header.
+    /// It can be used to reduce the volume of the downstream tracing.
+    #[inline]
+    pub fn set_sampled(&mut self, sampled: bool) {
+        self.sampled = Some(sampled);
     }
 
     /// Sets the trace context from headers.
@@ -432,10 +1131,170 @@ impl<S: ResponseCode> Response<S> {
     }
 
     /// Sends a cookie to the user agent.
+    ///
+    /// The value is percent-encoded, and the `Secure` attribute is enforced
+    /// if the `SameSite` attribute is `None`, as required by browsers.
+    /// Each cookie is emitted as a separate `set-cookie` header.
     #[cfg(feature = "cookie")]
-    #[inline]
     pub fn set_cookie(&mut self, cookie: &Cookie<'_>) {
-        self.insert_header("set-cookie", cookie.to_string());
+        if cookie.same_site() == Some(SameSite::None) && cookie.secure() != Some(true) {
+            let mut cookie = cookie.clone();
+            cookie.set_secure(true);
+            self.insert_header("set-cookie", cookie.encoded());
+        } else {
+            self.insert_header("set-cookie", cookie.encoded());
+        }
+    }
+
+    /// Sets the `alt-svc` header to advertise the alternative services, such as HTTP/3,
+    /// e.g. `h3=":443"; ma=86400`.
+    #[inline]
+    pub fn set_alt_svc(&mut self, value: impl Into<String>) {
+        self.remove_header("alt-svc");
+        self.insert_header("alt-svc", value.into());
+    }
+
+    /// Allows the origin to read the `server-timing` metrics via the `timing-allow-origin` header.
+    /// The origin can be `*` or a serialized origin such as `https://example.com`,
+    /// and the multiple origins are coalesced into one header.
+    /// Returns an error if the origin is invalid.
+    ///
+    /// If the response has a specific `access-control-allow-origin` header,
+    /// the wildcard is narrowed down to that origin in [`finalize()`](Self::finalize)
+    /// so that the two headers do not contradict each other.
+    pub fn allow_timing_origin(&mut self, origin: &str) -> Result<(), Error> {
+        let origin = if origin == "*" {
+            self.remove_header("timing-allow-origin");
+            origin.to_owned()
+        } else {
+            let url = Url::parse(origin)?;
+            let serialized_origin = url.origin().ascii_serialization();
+            if !matches!(url.scheme(), "http" | "https")
+                || serialized_origin != origin.trim_end_matches('/')
+            {
+                bail!(
+                    "invalid origin `{}` for the `timing-allow-origin` header",
+                    origin
+                );
+            }
+            serialized_origin
+        };
+        if let Some((_, value)) = self
+            .headers
+            .iter_mut()
+            .find(|(key, _)| key == "timing-allow-origin")
+        {
+            if value != "*" && !value.split(", ").any(|entry| entry == origin) {
+                value.push_str(", ");
+                value.push_str(&origin);
+            }
+        } else {
+            self.insert_header("timing-allow-origin", origin);
+        }
+        Ok(())
+    }
+
+    /// Adds a `link` header hint to preconnect to the origin.
+    #[inline]
+    pub fn add_preconnect(&mut self, url: &str) {
+        self.append_link(format!("<{url}>; rel=preconnect"));
+    }
+
+    /// Adds a `link` header hint to preload the resource of the `as` type.
+    /// Returns an error if the type is not one of the known request destinations.
+    ///
+    /// The `crossorigin` attribute is added for fonts and fetches as required by browsers.
+    pub fn add_preload(&mut self, url: &str, as_type: &str) -> Result<(), Error> {
+        const AS_TYPES: [&str; 12] = [
+            "audio", "document", "embed", "fetch", "font", "image", "object", "script", "style",
+            "track", "video", "worker",
+        ];
+        if !AS_TYPES.contains(&as_type) {
+            bail!("invalid `as` type `{}` for the preload link", as_type);
+        }
+
+        let mut link = format!("<{url}>; rel=preload; as={as_type}");
+        if matches!(as_type, "fetch" | "font") {
+            link.push_str("; crossorigin");
+        }
+        self.append_link(link);
+        Ok(())
+    }
+
+    /// Appends an entry to the `link` header, coalescing the multiple entries.
+    fn append_link(&mut self, link: String) {
+        if let Some((_, value)) = self.headers.iter_mut().find(|(key, _)| key == "link") {
+            if !value.split(", ").any(|entry| entry == link) {
+                value.push_str(", ");
+                value.push_str(&link);
+            }
+        } else {
+            self.insert_header("link", link);
+        }
+    }
+
+    /// Appends a header name to the `vary` header, coalescing the multiple names.
+    fn append_vary(&mut self, name: &str) {
+        if let Some((_, value)) = self.headers.iter_mut().find(|(key, _)| key == "vary") {
+            if !value
+                .split(',')
+                .any(|entry| entry.trim().eq_ignore_ascii_case(name))
+            {
+                value.push_str(", ");
+                value.push_str(name);
+            }
+        } else {
+            self.insert_header("vary", name);
+        }
+    }
+
+    /// Sets the security headers bundle.
+    ///
+    /// The headers are emitted in [`finalize()`](Self::finalize) only if the response
+    /// is an HTML document, unless [`SecurityHeaders::force()`] is enabled.
+    #[inline]
+    pub fn set_security_headers(&mut self, policy: SecurityHeaders) {
+        self.security_headers = Some(policy);
+    }
+
+    /// Sets the CORS configuration.
+    ///
+    /// The `access-control-*` headers are emitted in [`finalize()`](Self::finalize),
+    /// replacing the ones inserted manually, and `origin` is added to the `vary` header
+    /// if the allowed origin depends on the request.
+    #[inline]
+    pub fn set_cors(&mut self, cors: CorsConfig) {
+        self.cors = Some(cors);
+    }
+
+    /// Marks a response field as deprecated with a note.
+    ///
+    /// The deprecations are emitted as a `warning` header with the code `299`
+    /// in [`finalize()`](Self::finalize), and logged for the visibility of usages.
+    pub fn deprecate_field(&mut self, path: &str, note: &str) {
+        let note = note.to_owned().into();
+        if let Some(entry) = self
+            .deprecated_fields
+            .iter_mut()
+            .find(|(field, _)| field == path)
+        {
+            entry.1 = note;
+        } else {
+            self.deprecated_fields.push((path.to_owned().into(), note));
+        }
+    }
+
+    /// Enables the `content-digest` header computed with the algorithm.
+    ///
+    /// The digest is computed over the exact bytes returned by
+    /// [`read_bytes()`](Self::read_bytes). Calling it multiple times with different algorithms
+    /// emits them in one header. It has no effect for a streaming body.
+    #[inline]
+    pub fn set_content_digest(&mut self, algorithm: DigestAlgorithm) {
+        if !self.content_digest.contains(&algorithm) {
+            self.content_digest.push(algorithm);
+            self.invalidate_body();
+        }
     }
 
     /// Records a server timing metric entry.
@@ -452,29 +1311,109 @@ impl<S: ResponseCode> Response<S> {
             duration: Option<Duration>,
         ) {
             let metric = TimingMetric::new(name, description, duration);
+            res.collect_timing_spans();
             res.server_timing.push(metric);
         }
         inner::<S>(self, name.into(), description.into(), duration.into())
     }
 
+    /// Starts a timing span which records a server timing metric with the elapsed time
+    /// when it is dropped.
+    ///
+    /// The span does not borrow the response, so multiple spans can coexist.
+    /// The recorded metrics are collected before the `total` metric in
+    /// [`finalize()`](Self::finalize).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let span = res.start_timing("db");
+    /// let orders = Order::find(&query).await?;
+    /// drop(span);
+    /// ```
+    #[inline]
+    pub fn start_timing(&self, name: impl Into<SharedString>) -> TimingSpan {
+        TimingSpan::new(name.into(), self.timing_buffer.clone())
+    }
+
+    /// Collects the server timing metrics recorded by the spans.
+    fn collect_timing_spans(&mut self) {
+        let metrics = mem::take(&mut *self.timing_buffer.lock());
+        for metric in metrics {
+            self.server_timing.push(metric);
+        }
+    }
+
+    /// Sets the number of decimal places for the server timing durations in milliseconds.
+    #[inline]
+    pub fn set_server_timing_precision(&mut self, precision: usize) {
+        self.server_timing.set_precision(precision);
+    }
+
+    /// Enables or disables the aggregation of the server timing metrics with the same name,
+    /// in which case the durations are summed up in one entry.
+    #[inline]
+    pub fn set_server_timing_aggregated(&mut self, aggregated: bool) {
+        self.server_timing.set_aggregated(aggregated);
+    }
+
     /// Inserts a custom header.
     #[inline]
     pub fn insert_header(&mut self, name: impl Into<SharedString>, value: impl ToString) {
         self.headers.push((name.into(), value.to_string()));
     }
 
-    /// Gets a custome header with the given name.
+    /// Gets the first custom header with the given name, which is matched case-insensitively.
     #[inline]
     pub fn get_header(&self, name: &str) -> Option<&str> {
         self.headers
             .iter()
-            .find_map(|(key, value)| (key == name).then_some(value.as_str()))
+            .find_map(|(key, value)| key.eq_ignore_ascii_case(name).then_some(value.as_str()))
+    }
+
+    /// Gets all the custom headers with the given name, which is matched case-insensitively.
+    #[inline]
+    pub fn get_headers<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.headers.iter().filter_map(move |(key, value)| {
+            key.eq_ignore_ascii_case(name).then_some(value.as_str())
+        })
+    }
+
+    /// Removes all the custom headers with the given name, which is matched case-insensitively.
+    #[inline]
+    pub fn remove_header(&mut self, name: &str) {
+        self.headers
+            .retain(|(key, _)| !key.eq_ignore_ascii_case(name));
     }
 
     /// Returns the status code as `u16`.
+    /// It is always `200` for a gRPC-Web response, whose status is sent in the trailer frame.
     #[inline]
     pub fn status_code(&self) -> u16 {
-        self.status_code
+        if self.is_grpc_web() {
+            200
+        } else {
+            self.status_code
+        }
+    }
+
+    /// Returns `true` if the response has a gRPC-Web body.
+    #[inline]
+    fn is_grpc_web(&self) -> bool {
+        self.content_type().starts_with("application/grpc-web")
+    }
+
+    /// Returns the custom reason phrase if it is valid.
+    #[inline]
+    pub fn reason_phrase(&self) -> Option<&'static str> {
+        if self.is_grpc_web() {
+            return None;
+        }
+        self.reason_phrase.filter(|reason| {
+            reason
+                .bytes()
+                .all(|b| b == b'\t' || b == b' ' || b.is_ascii_graphic() || b >= 0x80)
+        })
     }
 
     /// Returns the error code.
@@ -540,6 +1479,12 @@ impl<S: ResponseCode> Response<S> {
         })
     }
 
+    /// Returns the locale.
+    #[inline]
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
     /// Returns the custom headers.
     #[inline]
     pub fn headers(&self) -> &[(SharedString, String)] {
@@ -547,17 +1492,27 @@ impl<S: ResponseCode> Response<S> {
     }
 
     /// Returns the trace context in the form `(traceparent, tracestate)`.
+    ///
+    /// The `sampled` flag is propagated from the parent `traceparent` by default,
+    /// which can be overridden by [`set_sampled()`](Self::set_sampled).
     pub fn trace_context(&self) -> (String, String) {
-        if let Some(ref trace_context) = self.trace_context {
-            (trace_context.traceparent(), trace_context.tracestate())
+        let mut trace_context = if let Some(ref trace_context) = self.trace_context {
+            if self.sampled.is_none() {
+                return (trace_context.traceparent(), trace_context.tracestate());
+            }
+            trace_context.clone()
         } else {
             let mut trace_context = TraceContext::new();
             let span_id = trace_context.span_id();
             trace_context
                 .trace_state_mut()
                 .push("zino", format!("{span_id:x}"));
-            (trace_context.traceparent(), trace_context.tracestate())
+            trace_context
+        };
+        if let Some(sampled) = self.sampled {
+            trace_context.set_sampled(sampled);
         }
+        (trace_context.traceparent(), trace_context.tracestate())
     }
 
     /// Returns the server timing.
@@ -566,15 +1521,491 @@ impl<S: ResponseCode> Response<S> {
         self.server_timing.to_string()
     }
 
+    /// Returns the recorded server timing metrics.
+    #[inline]
+    pub fn server_timing_metrics(&self) -> &[TimingMetric] {
+        self.server_timing.metrics()
+    }
+
+    /// Returns the effective response body as a JSON value.
+    ///
+    /// For a JSON response, it is the body encoded by [`read_bytes()`](Self::read_bytes)
+    /// before the bytes transformers and the compression, i.e. the envelope including
+    /// the `data` field, or the bare data for [`EnvelopeStyle::Bare`],
+    /// with the case style applied. Otherwise, it is the response data
+    /// before being transformed into bytes.
+    pub fn body_value(&mut self) -> Result<JsonValue, Error> {
+        if self.is_streaming() {
+            bail!("the streaming body can not be read as a JSON value");
+        }
+
+        let is_json = helper::check_json_content_type(self.content_type());
+        if !self.bytes_data.is_empty() {
+            if is_json {
+                Ok(serde_json::from_slice(&self.bytes_data)?)
+            } else {
+                bail!("the bytes data can not be read as a JSON value");
+            }
+        } else if is_json && self.data_transformer.is_none() {
+            let bytes = self.encode_bytes(&mut Vec::new())?;
+            Ok(serde_json::from_slice(&bytes)?)
+        } else {
+            Ok(self.json_data.clone())
+        }
+    }
+
+    /// Replaces the effective response body with a JSON value.
+    ///
+    /// It is the counterpart of [`body_value()`](Self::body_value). For a JSON response,
+    /// the value replaces the whole body to be sent as it is. Otherwise,
+    /// it replaces the response data.
+    pub fn set_body_value(&mut self, value: JsonValue) {
+        let content_type = self.content_type();
+        if helper::check_json_content_type(content_type) && self.data_transformer.is_none() {
+            self.content_type = Some(content_type.to_owned().into());
+            self.bytes_data = value.to_string().into();
+            self.invalidate_body();
+        } else {
+            self.json_data = value;
+            self.bytes_data = Bytes::new();
+            self.invalidate_body();
+        }
+    }
+
+    /// Returns `true` if the response has a streaming body.
+    #[inline]
+    pub fn is_streaming(&self) -> bool {
+        self.body_stream.is_some()
+    }
+
+    /// Takes the streaming body out of the response.
+    ///
+    /// It should be called before [`read_bytes()`](Self::read_bytes)
+    /// when converting the response into an HTTP response.
+    #[inline]
+    pub fn take_body_stream(&mut self) -> Option<BodyStream> {
+        let stream = self.body_stream.take().and_then(|body| body.take())?;
+        if let Some(limit) = self.max_body_size {
+            Some(body_limit::limit_body_stream(stream, limit))
+        } else {
+            Some(stream)
+        }
+    }
+
+    /// Sets a trailer whose value is computed by the `value_fn` at the end of the stream,
+    /// such as `server-timing`, `content-digest` or `grpc-status`.
+    ///
+    /// The trailers are sent via the HTTP trailers mechanism for a streaming body,
+    /// and they are dropped if it is not supported by the framework.
+    /// For other responses, they are computed in [`finalize()`](Self::finalize)
+    /// and sent as the headers.
+    #[inline]
+    pub fn set_trailer(
+        &mut self,
+        name: impl Into<SharedString>,
+        value_fn: impl Fn() -> String + Send + Sync + 'static,
+    ) {
+        self.trailers.insert(name.into(), Arc::new(value_fn));
+    }
+
+    /// Takes the trailers out of the response.
+    ///
+    /// It should be called along with [`take_body_stream()`](Self::take_body_stream)
+    /// before [`finalize()`](Self::finalize). The trailer names should be announced
+    /// by the `trailer` header if they are sent.
+    #[inline]
+    pub fn take_trailers(&mut self) -> Option<Trailers> {
+        (!self.trailers.is_empty()).then(|| mem::take(&mut self.trailers))
+    }
+
+    /// Negotiates the response body with the `prefer` header as defined in [RFC 7240].
+    ///
+    /// For a successful response, `return=minimal` yields a `204 No Content` without
+    /// calling the `body_builder`, while `return=representation` or the absence of
+    /// the preference builds the body. The `preference-applied` header is emitted
+    /// whenever the preference has been honored.
+    ///
+    /// [RFC 7240]: https://www.rfc-editor.org/rfc/rfc7240
+    pub fn prefer_return<Ctx, F>(mut self, ctx: &Ctx, body_builder: F) -> Self
+    where
+        Ctx: RequestContext,
+        F: FnOnce(&mut Self),
+    {
+        let preference = ctx.get_header("prefer").and_then(|header| {
+            header
+                .split([',', ';'])
+                .filter_map(|s| s.trim().split_once('='))
+                .find_map(|(key, value)| {
+                    key.trim()
+                        .eq_ignore_ascii_case("return")
+                        .then(|| value.trim().trim_matches('"'))
+                })
+        });
+        if self.is_success() && preference == Some("minimal") {
+            self.status_code = 204;
+            self.reason_phrase = None;
+            self.json_data = JsonValue::Null;
+            self.bytes_data = Bytes::new();
+            self.invalidate_body();
+            self.body_stream = None;
+            self.insert_header("preference-applied", "return=minimal");
+        } else {
+            body_builder(&mut self);
+            if preference == Some("representation") {
+                self.insert_header("preference-applied", "return=representation");
+            }
+        }
+        self
+    }
+
+    /// Enables the generation of a strong `etag` header from the response body.
+    ///
+    /// The entity tag is computed in [`read_bytes()`](Self::read_bytes) by hashing
+    /// the exact bytes to be sent, i.e. after the transformer and the compression,
+    /// unless the `etag` header has already been set.
+    #[inline]
+    pub fn enable_etag(&mut self) {
+        self.auto_etag = Some(false);
+        self.invalidate_body();
+    }
+
+    /// Enables the generation of a weak `etag` header from the response body.
+    /// See [`enable_etag()`](Self::enable_etag) for the details.
+    #[inline]
+    pub fn enable_weak_etag(&mut self) {
+        self.auto_etag = Some(true);
+        self.invalidate_body();
+    }
+
+    /// Checks the `if-none-match` header of the request against the entity tag of the response,
+    /// and returns `true` if the response has been short-circuited to `304 Not Modified`.
+    ///
+    /// The response keeps the `etag` header and the trace headers with an empty body.
+    pub fn check_if_none_match<Ctx: RequestContext>(&mut self, ctx: &Ctx) -> bool {
+        if !self.is_success() || !matches!(ctx.request_method(), "GET" | "HEAD") {
+            return false;
+        }
+
+        let Some(if_none_match) = ctx.get_header("if-none-match") else {
+            return false;
+        };
+        let matched = self
+            .entity_tag()
+            .is_some_and(|etag| conditional::match_entity_tags(if_none_match, &etag, true));
+        if matched {
+            self.set_not_modified();
+        }
+        matched
+    }
+
+    /// Evaluates the conditional request headers against the `etag` or `last-modified`
+    /// headers of the response, and returns `true` if the response has been short-circuited
+    /// to `304 Not Modified` or `412 Precondition Failed`.
+    ///
+    /// The preconditions are evaluated in the order defined by RFC 9110. In particular,
+    /// `If-None-Match` takes precedence over `If-Modified-Since`, and `If-Match` takes
+    /// precedence over `If-Unmodified-Since`. If the response has no `etag` header,
+    /// the entity tag is computed from the response body.
+    ///
+    /// It is the recommended entry point for handling conditional requests,
+    /// and should be called after the response data and headers have been set.
+    pub fn evaluate_preconditions<Ctx: RequestContext>(&mut self, ctx: &Ctx) -> bool {
+        if !self.is_success() {
+            return false;
+        }
+
+        let if_match = ctx.get_header("if-match");
+        let if_none_match = ctx.get_header("if-none-match");
+        let etag = if if_match.is_some() || if_none_match.is_some() {
+            self.entity_tag()
+        } else {
+            None
+        };
+        let last_modified = self
+            .get_header("last-modified")
+            .and_then(|s| DateTime::parse_utc_str(s).ok());
+
+        if let Some(if_match) = if_match {
+            let matched = etag
+                .as_ref()
+                .is_some_and(|etag| conditional::match_entity_tags(if_match, etag, false));
+            if !matched {
+                self.set_precondition_failed("the `if-match` precondition is not satisfied");
+                return true;
+            }
+        } else if let Some(if_unmodified_since) = ctx.get_header("if-unmodified-since") {
+            let modified = last_modified
+                .and_then(|dt| conditional::modified_since(if_unmodified_since, dt))
+                .unwrap_or_default();
+            if modified {
+                self.set_precondition_failed(
+                    "the `if-unmodified-since` precondition is not satisfied",
+                );
+                return true;
+            }
+        }
+
+        let is_safe_method = matches!(ctx.request_method(), "GET" | "HEAD");
+        if let Some(if_none_match) = if_none_match {
+            let matched = etag
+                .as_ref()
+                .is_some_and(|etag| conditional::match_entity_tags(if_none_match, etag, true));
+            if matched {
+                if is_safe_method {
+                    self.set_not_modified();
+                } else {
+                    self.set_precondition_failed(
+                        "the `if-none-match` precondition is not satisfied",
+                    );
+                }
+                return true;
+            }
+        } else if is_safe_method {
+            if let Some(if_modified_since) = ctx.get_header("if-modified-since") {
+                let modified = last_modified
+                    .and_then(|dt| conditional::modified_since(if_modified_since, dt))
+                    .unwrap_or(true);
+                if !modified {
+                    self.set_not_modified();
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the entity tag of the response.
+    fn entity_tag(&mut self) -> Option<EntityTag> {
+        if let Some(etag) = self.get_header("etag") {
+            return etag.parse().ok();
+        }
+        self.read_bytes().ok()?;
+        let etag = self
+            .encoded_headers()
+            .iter()
+            .find(|(name, _)| *name == "etag")
+            .or_else(|| {
+                self.encoded_headers()
+                    .iter()
+                    .find(|(name, _)| *name == "x-etag")
+            })?;
+        etag.1.parse().ok()
+    }
+
+    /// Short-circuits the response with `304 Not Modified`.
+    fn set_not_modified(&mut self) {
+        self.status_code = 304;
+        self.reason_phrase = None;
+        self.json_data = JsonValue::Null;
+        self.bytes_data = Bytes::new();
+        self.invalidate_body();
+        self.body_stream = None;
+    }
+
+    /// Short-circuits the response with `412 Precondition Failed`.
+    fn set_precondition_failed(&mut self, detail: &'static str) {
+        self.status_code = 412;
+        self.reason_phrase = None;
+        self.success = false;
+        self.title = Some("Precondition Failed".into());
+        self.detail = Some(detail.into());
+        self.message = None;
+        self.localization_key = None;
+        self.json_data = JsonValue::Null;
+        self.pagination = None;
+        self.bytes_data = Bytes::new();
+        self.invalidate_body();
+        self.body_stream = None;
+        self.content_type = None;
+        self.data_transformer = None;
+        self.bytes_transformers.clear();
+    }
+
     /// Reads the response into a byte buffer.
+    ///
+    /// The body is encoded only once, and the result is cached for the subsequent calls,
+    /// such as the ones in [`check_if_none_match()`](Self::check_if_none_match) and
+    /// the conversion into an HTTP response. The cache is invalidated when the response data,
+    /// the code, the message or any setting of the envelope and the encoding is changed.
+    ///
+    /// The encoding never consumes the settings of the response, and the headers derived
+    /// from the body, such as `content-encoding` and `etag`, are emitted in
+    /// [`finalize()`](Self::finalize) for the latest encoded body.
     pub fn read_bytes(&mut self) -> Result<Bytes, Error> {
+        let is_redirection =
+            (300..400).contains(&self.status_code) && self.get_header("location").is_some();
+        if matches!(self.status_code, 204 | 304) || is_redirection {
+            return Ok(Bytes::new());
+        }
+        if let Some((bytes, _)) = self.encoded_body.as_ref() {
+            return Ok(bytes.clone());
+        }
+
+        let mut headers = Vec::new();
+        let bytes = if self.is_grpc_web() {
+            self.encode_grpc_web_frames(&mut headers)?
+        } else {
+            self.transform_bytes(&mut headers)?
+        };
+        let bytes = self.compress_bytes(bytes, &mut headers)?;
+        if let Some(weak) = self.auto_etag {
+            if self.get_header("etag").is_none() {
+                let etag = EntityTag::from_data(&bytes);
+                headers.push(("etag", EntityTag::new(weak, etag.tag()).to_string()));
+            }
+        }
+        if !self.content_digest.is_empty() {
+            let digest = content_digest::format_content_digest(&bytes, &self.content_digest);
+            headers.push(("content-digest", digest));
+        }
+        self.encoded_body = Some((bytes.clone(), headers));
+        Ok(bytes)
+    }
+
+    /// Returns the headers derived from the body encoded by [`read_bytes()`](Self::read_bytes),
+    /// such as `content-encoding`, `etag` and `x-total-count`.
+    #[inline]
+    pub fn encoded_headers(&self) -> &[(&'static str, String)] {
+        self.encoded_body
+            .as_ref()
+            .map(|(_, headers)| headers.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Invalidates the encoded body cached by [`read_bytes()`](Self::read_bytes).
+    #[inline]
+    fn invalidate_body(&mut self) {
+        self.encoded_body = None;
+    }
+
+    /// Compresses the bytes with the compression algorithm if applicable.
+    fn compress_bytes(
+        &self,
+        bytes: Bytes,
+        headers: &mut Vec<(&'static str, String)>,
+    ) -> Result<Bytes, Error> {
+        let algo = self.compression;
+        if algo == CompressionAlgo::Identity
+            || bytes.len() < *compression::COMPRESSION_THRESHOLD
+            || self.get_header("content-encoding").is_some()
+            || !compression::is_compressible(self.content_type())
+        {
+            return Ok(bytes);
+        }
+        if let Some(data) = algo.compress(&bytes)? {
+            headers.push(("content-encoding", algo.as_str().to_owned()));
+            headers.push(("vary", "accept-encoding".to_owned()));
+            Ok(data)
+        } else {
+            Ok(bytes)
+        }
+    }
+
+    /// Encodes the response data as gRPC-Web frames.
+    fn encode_grpc_web_frames(
+        &mut self,
+        headers: &mut Vec<(&'static str, String)>,
+    ) -> Result<Bytes, Error> {
+        let status = grpc_web::grpc_status(self.status_code);
+        let (message, status_message) = if self.is_success() {
+            (Some(self.transform_bytes(headers)?), "")
+        } else {
+            let status_message = self
+                .detail
+                .as_deref()
+                .or(self.title.as_deref())
+                .unwrap_or_default();
+            (None, status_message)
+        };
+        let frames = grpc_web::encode_frames(message.as_deref(), status, status_message);
+        Ok(frames)
+    }
+
+    /// Encodes the response data as bytes and runs the bytes transformers in order.
+    fn transform_bytes(
+        &mut self,
+        headers: &mut Vec<(&'static str, String)>,
+    ) -> Result<Bytes, Error> {
+        let limit = self.body_size_limit();
+        let mut bytes = self.encode_bytes(headers)?;
+        body_limit::check_body_size(bytes.len(), limit)?;
+        for transformer in &self.bytes_transformers {
+            bytes = transformer(bytes)?;
+            body_limit::check_body_size(bytes.len(), limit)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Returns the max size of the buffered response body.
+    #[inline]
+    fn body_size_limit(&self) -> usize {
+        self.max_body_size.unwrap_or(*body_limit::MAX_BODY_SIZE)
+    }
+
+    /// Encodes the response data as bytes.
+    ///
+    /// The case style and the omission of the problem extensions are applied temporarily,
+    /// so that the response data is kept as it is for the subsequent encodings.
+    fn encode_bytes(&mut self, headers: &mut Vec<(&'static str, String)>) -> Result<Bytes, Error> {
+        let json_data = self.case_style.map(|case| {
+            let mut data = self.json_data.clone();
+            case.convert(&mut data);
+            mem::replace(&mut self.json_data, data)
+        });
+        let extensions = if self.content_type().starts_with("application/problem+json") {
+            None
+        } else {
+            Some(mem::take(&mut self.extensions))
+        };
+        let result = self.encode_data(headers);
+        if let Some(data) = json_data {
+            self.json_data = data;
+        }
+        if let Some(extensions) = extensions {
+            self.extensions = extensions;
+        }
+        result
+    }
+
+    /// Encodes the response data as bytes without the case style being applied.
+    fn encode_data(&self, headers: &mut Vec<(&'static str, String)>) -> Result<Bytes, Error> {
+        let content_type = self.content_type();
+        let is_bare = self.envelope_style == EnvelopeStyle::Bare
+            && self.is_success()
+            && crate::helper::check_json_content_type(content_type);
+        if is_bare {
+            if let Some(page) = self.pagination.as_ref() {
+                let page_headers = [
+                    ("x-total-count", page.total_count()),
+                    ("x-page-size", page.page_size()),
+                    ("x-current-page", page.current_page()),
+                    ("x-total-pages", page.total_pages()),
+                ];
+                for (name, value) in page_headers {
+                    headers.push((name, value.to_string()));
+                }
+            }
+        }
+
         let has_bytes_data = !self.bytes_data.is_empty();
         let has_json_data = !self.json_data.is_null();
         let bytes_opt = if has_bytes_data {
             Some(self.bytes_data.clone())
         } else if has_json_data {
-            if let Some(transformer) = self.data_transformer.as_ref() {
-                Some(transformer(&self.json_data)?)
+            if let Some(transformer) = self.data_transformer {
+                let data = &self.json_data;
+                let bytes = match transformer {
+                    Transformer::Simple(transformer) => transformer(data)?,
+                    Transformer::Contextual(transformer) => {
+                        let ctx = TransformContext::new(
+                            self.content_type(),
+                            self.locale(),
+                            self.is_success(),
+                        );
+                        transformer(data, &ctx)?
+                    }
+                };
+                Some(bytes)
             } else {
                 None
             }
@@ -583,28 +2014,59 @@ impl<S: ResponseCode> Response<S> {
         };
         if let Some(bytes) = bytes_opt {
             let etag = EntityTag::from_data(&bytes);
-            self.insert_header("x-etag", etag);
+            headers.push(("x-etag", etag.to_string()));
             return Ok(bytes);
         }
 
-        let content_type = self.content_type();
+        let limit = self.body_size_limit();
         let (bytes, etag_opt) = if crate::helper::check_json_content_type(content_type) {
             let (capacity, etag_opt) = if has_json_data {
-                let data = serde_json::to_vec(&self.json_data)?;
+                let mut writer = LimitedWriter::with_capacity(0, limit);
+                serde_json::to_writer(&mut writer, &self.json_data)?;
+                let data = writer.into_inner();
                 let etag = EntityTag::from_data(&data);
                 (data.len() + 128, Some(etag))
             } else {
                 (128, None)
             };
-            let mut bytes = Vec::with_capacity(capacity);
-            serde_json::to_writer(&mut bytes, &self)?;
-            (bytes, etag_opt)
+            let mut writer = LimitedWriter::with_capacity(capacity, limit);
+            if is_bare {
+                if has_json_data {
+                    serde_json::to_writer(&mut writer, &self.json_data)?;
+                } else if self.pagination.is_some() {
+                    serde_json::to_writer(&mut writer, &JsonValue::Array(Vec::new()))?;
+                } else {
+                    serde_json::to_writer(&mut writer, &Map::new())?;
+                }
+            } else {
+                serde_json::to_writer(&mut writer, &self)?;
+            }
+            (writer.into_inner(), etag_opt)
         } else if has_json_data {
             let value = &self.json_data;
+            let writer = LimitedWriter::with_capacity(0, limit);
             let bytes = if content_type.starts_with("text/csv") {
-                value.to_csv(Vec::new())?
+                if let Some(options) = self.csv_options.as_ref() {
+                    options.to_csv(value, writer)?.into_inner()
+                } else {
+                    CsvOptions::default().to_csv(value, writer)?.into_inner()
+                }
             } else if content_type.starts_with("application/jsonlines") {
-                value.to_jsonlines(Vec::new())?
+                let mut writer = writer;
+                let values = match value {
+                    JsonValue::Array(vec) => vec.as_slice(),
+                    _ => std::slice::from_ref(value),
+                };
+                for value in values {
+                    serde_json::to_writer(&mut writer, value)?;
+                    writer.write_all(b"\n")?;
+                }
+                writer.into_inner()
+            } else if cfg!(feature = "xml") && content_type.starts_with("application/xml") {
+                let root = self.xml_root.as_deref().unwrap_or("root");
+                xml::to_xml(value, root, writer)?.into_inner()
+            } else if cfg!(feature = "toml") && content_type.starts_with("application/toml") {
+                toml_format::to_toml(value)?
             } else if let JsonValue::String(s) = value {
                 s.as_bytes().to_vec()
             } else {
@@ -615,7 +2077,7 @@ impl<S: ResponseCode> Response<S> {
             (Vec::new(), None)
         };
         let etag = etag_opt.unwrap_or_else(|| EntityTag::from_data(&bytes));
-        self.insert_header("x-etag", etag);
+        headers.push(("x-etag", etag.to_string()));
         Ok(bytes.into())
     }
 
@@ -646,10 +2108,7 @@ impl<S: ResponseCode> Response<S> {
         }
         if !displayed_inline {
             if let Some(file_name) = file.file_name() {
-                self.insert_header(
-                    "content-disposition",
-                    format!(r#"attachment; filename="{file_name}""#),
-                );
+                self.set_attachment(file_name);
             }
         }
         self.insert_header("etag", file.etag());
@@ -658,6 +2117,16 @@ impl<S: ResponseCode> Response<S> {
 
     /// Consumes `self` and returns the custom headers.
     pub fn finalize(mut self) -> impl Iterator<Item = (SharedString, String)> {
+        if let Some((_, headers)) = self.encoded_body.take() {
+            for (name, value) in headers {
+                if name == "vary" {
+                    self.append_vary(&value);
+                } else {
+                    self.insert_header(name, value);
+                }
+            }
+        }
+
         let request_id = self.request_id();
         if !request_id.is_nil() {
             self.insert_header("x-request-id", request_id.to_string());
@@ -667,10 +2136,90 @@ impl<S: ResponseCode> Response<S> {
         self.insert_header("traceparent", traceparent);
         self.insert_header("tracestate", tracestate);
 
+        self.collect_timing_spans();
         let duration = self.response_time();
         self.record_server_timing("total", None, Some(duration));
         self.insert_header("server-timing", self.server_timing());
 
+        if !self.deprecated_fields.is_empty() {
+            let warnings = self
+                .deprecated_fields
+                .iter()
+                .map(|(field, note)| {
+                    let text = format!("field {field} deprecated: {note}");
+                    let text = text.replace('\\', "\\\\").replace('"', "\\\"");
+                    format!("299 - \"{text}\"")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let fields = self
+                .deprecated_fields
+                .iter()
+                .map(|(field, _)| field.as_ref())
+                .collect::<Vec<_>>()
+                .join(", ");
+            tracing::warn!(
+                request_id = request_id.to_string(),
+                fields,
+                "deprecated response fields are used"
+            );
+            self.insert_header("warning", warnings);
+        }
+
+        if let Some(retry_after) = self.retry_after.take() {
+            if (200..300).contains(&self.status_code) {
+                tracing::debug!(
+                    status_code = self.status_code,
+                    "the `retry-after` header is ignored for a successful response"
+                );
+            } else {
+                self.insert_header("retry-after", retry_after.to_header_value());
+            }
+        }
+
+        for (name, value) in mem::take(&mut self.trailers).compute() {
+            self.insert_header(name, value);
+        }
+
+        if let Some(cors) = self.cors.take() {
+            let (headers, vary_origin) = cors.headers();
+            self.headers.retain(|(key, _)| {
+                !key.starts_with("access-control-allow-")
+                    && key != "access-control-expose-headers"
+                    && key != "access-control-max-age"
+            });
+            for (name, value) in headers {
+                self.insert_header(name, value);
+            }
+            if vary_origin {
+                self.append_vary("origin");
+            }
+        }
+
+        let allow_origin = self
+            .get_header("access-control-allow-origin")
+            .filter(|&origin| origin != "*")
+            .map(|origin| origin.to_owned());
+        if let Some(allow_origin) = allow_origin {
+            if let Some((_, value)) = self
+                .headers
+                .iter_mut()
+                .find(|(key, _)| key == "timing-allow-origin")
+            {
+                if value == "*" {
+                    *value = allow_origin;
+                }
+            }
+        }
+
+        if let Some(policy) = self.security_headers.take() {
+            if policy.applies_to(self.content_type()) {
+                for (name, value) in policy.headers() {
+                    self.insert_header(name, value);
+                }
+            }
+        }
+
         self.headers.into_iter()
     }
 }
@@ -688,6 +2237,56 @@ impl Response<StatusCode> {
         Response::new(StatusCode::CREATED)
     }
 
+    /// Constructs a redirection to the location with an empty body.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use zino_core::response::{RedirectKind, Response};
+    ///
+    /// // POST /orders
+    /// async fn create(req: Request) -> Result {
+    ///     let order_id = Order::create(&req).await?;
+    ///     let location = format!("/orders/{order_id}");
+    ///     let res = Response::redirect(location, RedirectKind::SeeOther).context(&req);
+    ///     Ok(res.into())
+    /// }
+    /// ```
+    pub fn redirect(location: impl Into<SharedString>, kind: RedirectKind) -> Self {
+        let mut res = Response::new(kind.status_code());
+        res.success = true;
+        res.title = None;
+        res.detail = None;
+        res.insert_header("location", location.into());
+        res
+    }
+
+    /// Constructs a `204 No Content` response to answer the CORS preflight request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use zino_core::response::{CorsConfig, Response};
+    ///
+    /// // OPTIONS /orders
+    /// async fn preflight(req: Request) -> Result {
+    ///     let cors = CorsConfig::new()
+    ///         .allow_origins(["https://example.com"])
+    ///         .allow_methods(["GET", "POST"])
+    ///         .allow_credentials(true)
+    ///         .request_origin(req.get_header("origin").unwrap_or_default().to_owned());
+    ///     let res = Response::preflight(cors).context(&req);
+    ///     Ok(res.into())
+    /// }
+    /// ```
+    pub fn preflight(cors: CorsConfig) -> Self {
+        let mut res = Response::new(StatusCode::NO_CONTENT);
+        res.success = true;
+        res.message = None;
+        res.set_cors(cors);
+        res
+    }
+
     /// Constructs a new response with status `400 Bad Request`.
     #[inline]
     pub fn bad_request() -> Self {
@@ -725,3 +2324,109 @@ impl<S: ResponseCode> From<Validation> for Response<S> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CaseStyle, CompressionAlgo, EnvelopeStyle, Page, Response, StatusCode};
+    use crate::{error::Error, json, JsonValue};
+    use bytes::Bytes;
+
+    fn append_mark(bytes: Bytes) -> Result<Bytes, Error> {
+        Ok([&bytes[..], b"!"].concat().into())
+    }
+
+    #[test]
+    fn it_invalidates_encoded_body() {
+        let mut res = Response::new(StatusCode::OK);
+        res.set_json_data(json!({ "user_name": "alice" }));
+        let bytes = res.read_bytes().unwrap();
+        assert_eq!(res.read_bytes().unwrap(), bytes);
+
+        res.set_message("updated");
+        let bytes = res.read_bytes().unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("updated"));
+
+        res.set_case(CaseStyle::Camel);
+        let bytes = res.read_bytes().unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("userName"));
+
+        res.set_envelope(EnvelopeStyle::Bare);
+        assert_eq!(res.read_bytes().unwrap(), r#"{"userName":"alice"}"#);
+
+        res.set_code(StatusCode::BAD_REQUEST);
+        let bytes = res.read_bytes().unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("400"));
+
+        res.set_status_code(StatusCode::OK.as_u16());
+        res.set_content_type("application/jsonlines");
+        assert_eq!(res.read_bytes().unwrap(), "{\"userName\":\"alice\"}\n");
+
+        if cfg!(feature = "compression-gzip") {
+            let mut res = Response::new(StatusCode::OK);
+            res.set_json_data(json!({ "text": "a".repeat(2048) }));
+            res.set_compression(CompressionAlgo::Gzip);
+            let bytes = res.read_bytes().unwrap();
+            assert_eq!(bytes[..2], [0x1f, 0x8b]);
+            res.set_message("updated");
+            let bytes = res.read_bytes().unwrap();
+            assert_eq!(bytes[..2], [0x1f, 0x8b]);
+            assert!(res
+                .encoded_headers()
+                .contains(&("content-encoding", "gzip".to_owned())));
+            let headers = res.finalize().collect::<Vec<_>>();
+            assert!(headers
+                .iter()
+                .any(|(name, value)| name == "content-encoding" && value == "gzip"));
+        }
+
+        let mut res = Response::new(StatusCode::OK);
+        res.set_content_type("text/plain");
+        res.set_data(&"alice");
+        res.push_data_transformer(append_mark);
+        assert_eq!(res.read_bytes().unwrap(), "alice!");
+        res.set_data(&"bob");
+        assert_eq!(res.read_bytes().unwrap(), "bob!");
+
+        let mut res = Response::new(StatusCode::OK);
+        res.set_json_data(json!({ "user_name": "alice" }));
+        res.enable_etag();
+        let etag = res.entity_tag().unwrap();
+        res.set_message("updated");
+        let bytes = res.read_bytes().unwrap();
+        assert_ne!(res.entity_tag().unwrap(), etag);
+        assert_eq!(
+            res.entity_tag().unwrap(),
+            super::EntityTag::from_data(&bytes)
+        );
+    }
+
+    #[test]
+    fn it_round_trips_body_value() {
+        let mut res = Response::new(StatusCode::OK);
+        res.set_envelope(EnvelopeStyle::Bare);
+        res.set_page(&["a", "b"], Page::new(12, 2, 3));
+        let bytes = res.read_bytes().unwrap();
+        let value = res.body_value().unwrap();
+        assert_eq!(value, json!(["a", "b"]));
+        res.set_body_value(value.clone());
+        assert_eq!(res.read_bytes().unwrap(), bytes);
+        assert_eq!(res.body_value().unwrap(), value);
+        assert!(res
+            .encoded_headers()
+            .contains(&("x-total-count", "12".to_owned())));
+
+        let mut res = Response::new(StatusCode::OK);
+        res.set_json_data(json!({ "user_name": "alice" }));
+        res.set_case(CaseStyle::Camel);
+        let bytes = res.read_bytes().unwrap();
+        let value = res.body_value().unwrap();
+        assert_eq!(value["data"], json!({ "userName": "alice" }));
+        res.set_body_value(value.clone());
+        let data = res.read_bytes().unwrap();
+        assert_eq!(
+            serde_json::from_slice::<JsonValue>(&data).unwrap(),
+            serde_json::from_slice::<JsonValue>(&bytes).unwrap()
+        );
+        assert_eq!(res.body_value().unwrap(), value);
+    }
+}
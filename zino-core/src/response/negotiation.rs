@@ -0,0 +1,75 @@
+/// Negotiates the media type with the `accept` header value.
+///
+/// The quality of a supported type is determined by the most specific media range
+/// matching it, where `type/subtype` takes precedence over `type/*` and `*/*`.
+/// The supported type with the highest quality is chosen, and the ties are broken
+/// by the order of the `supported` types. Returns `None` if nothing is acceptable.
+pub(super) fn negotiate_media_type<'a>(accept: &str, supported: &[&'a str]) -> Option<&'a str> {
+    let media_ranges = accept
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let media_range = parts.next()?.trim().to_ascii_lowercase();
+            let (main_type, subtype) = media_range.split_once('/')?;
+            let (main_type, subtype) = (main_type.to_owned(), subtype.to_owned());
+            let quality = parts
+                .find_map(|s| s.trim().strip_prefix("q="))
+                .map(|s| s.trim().parse::<f32>().unwrap_or_default())
+                .unwrap_or(1.0);
+            Some((main_type, subtype, quality))
+        })
+        .collect::<Vec<_>>();
+
+    let mut best_match = None;
+    let mut max_quality = 0.0;
+    for &media_type in supported {
+        let (main_type, subtype) = media_type.split_once('/').unwrap_or((media_type, ""));
+        let quality = media_ranges
+            .iter()
+            .filter_map(|(range_type, range_subtype, quality)| {
+                let specificity = if range_type == main_type && range_subtype == subtype {
+                    2
+                } else if range_type == main_type && range_subtype == "*" {
+                    1
+                } else if range_type == "*" && range_subtype == "*" {
+                    0
+                } else {
+                    return None;
+                };
+                Some((specificity, *quality))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map(|(_, quality)| quality);
+        if let Some(quality) = quality {
+            if quality > max_quality {
+                best_match = Some(media_type);
+                max_quality = quality;
+            }
+        }
+    }
+    best_match
+}
+
+#[cfg(test)]
+mod tests {
+    use super::negotiate_media_type;
+
+    #[test]
+    fn it_negotiates_media_type() {
+        let supported = ["application/json", "text/csv"];
+        let accept = "text/csv;q=0.9, application/json;q=1.0";
+        assert_eq!(
+            negotiate_media_type(accept, &supported),
+            Some("application/json")
+        );
+        assert_eq!(
+            negotiate_media_type("text/*, */*;q=0.1", &supported),
+            Some("text/csv")
+        );
+        assert_eq!(
+            negotiate_media_type("*/*, application/json;q=0", &supported),
+            Some("text/csv")
+        );
+        assert_eq!(negotiate_media_type("image/png", &supported), None);
+    }
+}
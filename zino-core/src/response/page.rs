@@ -0,0 +1,156 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use url::Url;
+
+/// Pagination metadata for a list response.
+///
+/// It is serialized as the `pagination` field with a consistent shape:
+/// `total_count`, `page_size`, `current_page`, `total_pages`, `has_next` and `has_prev`.
+/// The pages are numbered from `1`.
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// Total number of the items.
+    total_count: usize,
+    /// Number of the items in a page.
+    page_size: usize,
+    /// Current page number.
+    current_page: usize,
+    /// URL used to generate the links for the adjacent pages.
+    link_url: Option<Url>,
+}
+
+impl Page {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(total_count: usize, page_size: usize, current_page: usize) -> Self {
+        Self {
+            total_count,
+            page_size,
+            current_page: current_page.max(1),
+            link_url: None,
+        }
+    }
+
+    /// Sets the URL to generate the `link` header with `rel="next"` and `rel="prev"`,
+    /// where the `current_page` and `page_size` query parameters are replaced.
+    #[inline]
+    pub fn link_url(mut self, url: Url) -> Self {
+        self.link_url = Some(url);
+        self
+    }
+
+    /// Returns the total number of the items.
+    #[inline]
+    pub fn total_count(&self) -> usize {
+        self.total_count
+    }
+
+    /// Returns the number of the items in a page.
+    #[inline]
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Returns the current page number.
+    #[inline]
+    pub fn current_page(&self) -> usize {
+        self.current_page
+    }
+
+    /// Returns the total number of the pages.
+    #[inline]
+    pub fn total_pages(&self) -> usize {
+        if self.page_size == 0 {
+            0
+        } else {
+            self.total_count.div_ceil(self.page_size)
+        }
+    }
+
+    /// Returns `true` if there is a next page.
+    #[inline]
+    pub fn has_next(&self) -> bool {
+        self.current_page < self.total_pages()
+    }
+
+    /// Returns `true` if there is a previous page.
+    #[inline]
+    pub fn has_prev(&self) -> bool {
+        self.current_page > 1
+    }
+
+    /// Returns the entries of the `link` header for the adjacent pages.
+    pub(super) fn links(&self) -> Vec<String> {
+        let mut links = Vec::new();
+        if let Some(url) = self.link_url.as_ref() {
+            if self.has_prev() {
+                let url = self.page_url(url, self.current_page - 1);
+                links.push(format!(r#"<{url}>; rel="prev""#));
+            }
+            if self.has_next() {
+                let url = self.page_url(url, self.current_page + 1);
+                links.push(format!(r#"<{url}>; rel="next""#));
+            }
+        }
+        links
+    }
+
+    /// Returns the URL for the page number.
+    fn page_url(&self, url: &Url, page: usize) -> Url {
+        let mut url = url.clone();
+        let pairs = url
+            .query_pairs()
+            .filter(|(key, _)| key != "current_page" && key != "page_size")
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect::<Vec<_>>();
+        url.query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs)
+            .append_pair("current_page", &page.to_string())
+            .append_pair("page_size", &self.page_size.to_string());
+        url
+    }
+}
+
+impl Serialize for Page {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Page", 6)?;
+        state.serialize_field("total_count", &self.total_count)?;
+        state.serialize_field("page_size", &self.page_size)?;
+        state.serialize_field("current_page", &self.current_page)?;
+        state.serialize_field("total_pages", &self.total_pages())?;
+        state.serialize_field("has_next", &self.has_next())?;
+        state.serialize_field("has_prev", &self.has_prev())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Page;
+    use url::Url;
+
+    #[test]
+    fn it_computes_pagination() {
+        let url = Url::parse("https://example.com/user/list?sort=name&current_page=2").unwrap();
+        let page = Page::new(45, 10, 2).link_url(url);
+        assert_eq!(
+            serde_json::to_value(&page).unwrap(),
+            serde_json::json!({
+                "total_count": 45,
+                "page_size": 10,
+                "current_page": 2,
+                "total_pages": 5,
+                "has_next": true,
+                "has_prev": true,
+            })
+        );
+        assert_eq!(
+            page.links(),
+            [
+                r#"<https://example.com/user/list?sort=name&current_page=1&page_size=10>; rel="prev""#,
+                r#"<https://example.com/user/list?sort=name&current_page=3&page_size=10>; rel="next""#,
+            ]
+        );
+        assert!(!Page::new(0, 10, 1).has_next());
+    }
+}
@@ -0,0 +1,31 @@
+use super::StatusCode;
+
+/// Kinds of HTTP redirections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RedirectKind {
+    /// `301 Moved Permanently`.
+    MovedPermanently,
+    /// `302 Found`.
+    Found,
+    /// `303 See Other`. It is typically used after a `POST` to redirect to a `GET`.
+    SeeOther,
+    /// `307 Temporary Redirect`. The method and the body are preserved.
+    TemporaryRedirect,
+    /// `308 Permanent Redirect`. The method and the body are preserved.
+    PermanentRedirect,
+}
+
+impl RedirectKind {
+    /// Returns the status code of the redirection.
+    #[inline]
+    pub fn status_code(self) -> StatusCode {
+        match self {
+            Self::MovedPermanently => StatusCode::MOVED_PERMANENTLY,
+            Self::Found => StatusCode::FOUND,
+            Self::SeeOther => StatusCode::SEE_OTHER,
+            Self::TemporaryRedirect => StatusCode::TEMPORARY_REDIRECT,
+            Self::PermanentRedirect => StatusCode::PERMANENT_REDIRECT,
+        }
+    }
+}
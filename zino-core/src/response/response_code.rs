@@ -54,6 +54,18 @@ pub trait ResponseCode {
     fn message(&self) -> Option<SharedString> {
         None
     }
+
+    /// A key to localize the title and the message, such as `http-404`.
+    /// The translations are looked up as `{key}-title` and `{key}-message`.
+    fn localization_key(&self) -> Option<SharedString> {
+        None
+    }
+
+    /// A custom reason phrase in the status line, such as `Partial Success` for `299`.
+    /// If it is `None`, the canonical reason phrase is used.
+    fn reason_phrase(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 impl ResponseCode for StatusCode {
@@ -96,4 +108,19 @@ impl ResponseCode for StatusCode {
             None
         }
     }
+
+    #[inline]
+    fn localization_key(&self) -> Option<SharedString> {
+        Some(format!("http-{}", self.as_u16()).into())
+    }
+}
+
+/// Normalizes the status code, falling back to `500` for an invalid code.
+pub(super) fn normalize_status_code(status_code: u16) -> u16 {
+    if (100..600).contains(&status_code) {
+        status_code
+    } else {
+        tracing::warn!(status_code, "invalid status code is replaced by `500`");
+        500
+    }
 }
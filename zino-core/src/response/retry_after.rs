@@ -0,0 +1,36 @@
+use crate::datetime::DateTime;
+use std::time::Duration;
+
+/// Values of the `retry-after` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RetryAfter {
+    /// A delay emitted as the delta-seconds.
+    Delay(Duration),
+    /// An absolute time emitted as the HTTP-date.
+    Date(DateTime),
+}
+
+impl RetryAfter {
+    /// Returns the header value.
+    pub fn to_header_value(&self) -> String {
+        match self {
+            Self::Delay(duration) => duration.as_secs().to_string(),
+            Self::Date(datetime) => datetime.to_utc_string(),
+        }
+    }
+}
+
+impl From<Duration> for RetryAfter {
+    #[inline]
+    fn from(duration: Duration) -> Self {
+        Self::Delay(duration)
+    }
+}
+
+impl From<DateTime> for RetryAfter {
+    #[inline]
+    fn from(datetime: DateTime) -> Self {
+        Self::Date(datetime)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::SharedString;
+
+/// A bundle of security headers for HTML documents.
+///
+/// By default, it enables `x-content-type-options: nosniff`, denies framing
+/// via `x-frame-options: DENY`, and uses the `strict-origin-when-cross-origin` referrer policy.
+/// The headers are only emitted for HTML responses unless [`force()`](Self::force) is enabled.
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    /// The `content-security-policy` header value.
+    content_security_policy: Option<SharedString>,
+    /// The `referrer-policy` header value.
+    referrer_policy: Option<SharedString>,
+    /// The `x-frame-options` header value.
+    frame_options: Option<SharedString>,
+    /// A flag to emit `x-content-type-options: nosniff`.
+    nosniff: bool,
+    /// A flag to emit the headers for non-HTML responses.
+    forced: bool,
+}
+
+impl SecurityHeaders {
+    /// Creates a new instance with the safe defaults.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            content_security_policy: None,
+            referrer_policy: Some("strict-origin-when-cross-origin".into()),
+            frame_options: Some("DENY".into()),
+            nosniff: true,
+            forced: false,
+        }
+    }
+
+    /// Sets the `content-security-policy` header value.
+    #[inline]
+    pub fn content_security_policy(mut self, policy: impl Into<SharedString>) -> Self {
+        self.content_security_policy = Some(policy.into());
+        self
+    }
+
+    /// Sets the `referrer-policy` header value. An empty value disables the header.
+    #[inline]
+    pub fn referrer_policy(mut self, policy: impl Into<SharedString>) -> Self {
+        let policy = policy.into();
+        self.referrer_policy = (!policy.is_empty()).then_some(policy);
+        self
+    }
+
+    /// Sets the `x-frame-options` header value: `DENY` | `SAMEORIGIN`.
+    /// An empty value disables the header.
+    #[inline]
+    pub fn frame_options(mut self, options: impl Into<SharedString>) -> Self {
+        let options = options.into();
+        self.frame_options = (!options.is_empty()).then_some(options);
+        self
+    }
+
+    /// Enables or disables `x-content-type-options: nosniff`.
+    #[inline]
+    pub fn nosniff(mut self, nosniff: bool) -> Self {
+        self.nosniff = nosniff;
+        self
+    }
+
+    /// Emits the headers even if the response is not an HTML document.
+    #[inline]
+    pub fn force(mut self, forced: bool) -> Self {
+        self.forced = forced;
+        self
+    }
+
+    /// Returns `true` if the headers should be emitted for the content type.
+    pub(super) fn applies_to(&self, content_type: &str) -> bool {
+        self.forced
+            || content_type.starts_with("text/html")
+            || content_type.starts_with("application/xhtml+xml")
+    }
+
+    /// Returns the headers to be emitted.
+    pub(super) fn headers(&self) -> Vec<(&'static str, SharedString)> {
+        let mut headers = Vec::with_capacity(4);
+        if let Some(policy) = self.content_security_policy.as_ref() {
+            headers.push(("content-security-policy", policy.clone()));
+        }
+        if self.nosniff {
+            headers.push(("x-content-type-options", "nosniff".into()));
+        }
+        if let Some(policy) = self.referrer_policy.as_ref() {
+            headers.push(("referrer-policy", policy.clone()));
+        }
+        if let Some(options) = self.frame_options.as_ref() {
+            headers.push(("x-frame-options", options.clone()));
+        }
+        headers
+    }
+}
+
+impl Default for SecurityHeaders {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,92 @@
+use crate::JsonValue;
+use bytes::Bytes;
+use futures::{
+    future::{self, Either},
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use futures_timer::Delay;
+use std::time::Duration;
+
+/// A comment line to keep the connection alive.
+const KEEP_ALIVE_COMMENT: &[u8] = b":\n\n";
+
+/// Formats the JSON value as a server-sent event.
+///
+/// The reserved keys `$id`, `$event` and `$retry` of an object are emitted
+/// as the corresponding fields. The value of `$data` is used as the event data
+/// if it exists, otherwise the remaining object is used.
+pub(super) fn format_event(value: JsonValue) -> String {
+    let mut event = String::new();
+    let data = if let JsonValue::Object(mut map) = value {
+        for key in ["$id", "$event"] {
+            if let Some(value) = map.remove(key) {
+                let value = match value {
+                    JsonValue::String(s) => s,
+                    _ => value.to_string(),
+                };
+                let field = key.trim_start_matches('$');
+                let value = value.replace(['\r', '\n'], "");
+                event.push_str(&format!("{field}: {value}\n"));
+            }
+        }
+        if let Some(retry) = map.remove("$retry").and_then(|v| v.as_u64()) {
+            event.push_str(&format!("retry: {retry}\n"));
+        }
+        match map.remove("$data") {
+            Some(JsonValue::String(s)) => s,
+            Some(data) => data.to_string(),
+            None => JsonValue::Object(map).to_string(),
+        }
+    } else {
+        value.to_string()
+    };
+    for line in data.lines() {
+        event.push_str("data: ");
+        event.push_str(line);
+        event.push('\n');
+    }
+    event.push('\n');
+    event
+}
+
+/// Interleaves the events with the keep-alive comments
+/// when there is no event in the interval.
+pub(super) fn keep_alive(
+    events: BoxStream<'static, Bytes>,
+    interval: Duration,
+) -> BoxStream<'static, Bytes> {
+    let state = (events, Delay::new(interval));
+    stream::unfold(state, move |(mut events, mut delay)| async move {
+        match future::select(events.next(), &mut delay).await {
+            Either::Left((Some(event), _)) => {
+                delay.reset(interval);
+                Some((event, (events, delay)))
+            }
+            Either::Left((None, _)) => None,
+            Either::Right(_) => {
+                delay.reset(interval);
+                Some((Bytes::from_static(KEEP_ALIVE_COMMENT), (events, delay)))
+            }
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_event;
+
+    #[test]
+    fn it_formats_sse_events() {
+        let event = format_event(serde_json::json!({
+            "$id": 1,
+            "$event": "update",
+            "count": 3,
+        }));
+        assert_eq!(event, "id: 1\nevent: update\ndata: {\"count\":3}\n\n");
+
+        let event = format_event(serde_json::json!({ "$data": "one\ntwo" }));
+        assert_eq!(event, "data: one\ndata: two\n\n");
+    }
+}
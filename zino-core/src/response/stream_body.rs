@@ -0,0 +1,76 @@
+use crate::{error::Error, SharedString};
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use parking_lot::Mutex;
+use std::{fmt, sync::Arc};
+
+/// A stream of the response body chunks.
+pub type BodyStream = BoxStream<'static, Result<Bytes, Error>>;
+
+/// A streaming body which can be taken only once.
+#[derive(Clone)]
+pub(super) struct StreamBody(Arc<Mutex<Option<BodyStream>>>);
+
+impl StreamBody {
+    /// Creates a new instance.
+    #[inline]
+    pub(super) fn new(stream: BodyStream) -> Self {
+        Self(Arc::new(Mutex::new(Some(stream))))
+    }
+
+    /// Takes the stream out of the body, leaving `None` in its place.
+    #[inline]
+    pub(super) fn take(&self) -> Option<BodyStream> {
+        self.0.lock().take()
+    }
+}
+
+impl fmt::Debug for StreamBody {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamBody").finish_non_exhaustive()
+    }
+}
+
+/// A function to compute the trailer value at the end of the stream.
+type TrailerFn = Arc<dyn Fn() -> String + Send + Sync>;
+
+/// HTTP trailers sent after the streaming body.
+#[derive(Clone, Default)]
+pub struct Trailers(Vec<(SharedString, TrailerFn)>);
+
+impl Trailers {
+    /// Inserts a trailer, replacing the existing one with the same name.
+    pub(super) fn insert(&mut self, name: SharedString, value_fn: TrailerFn) {
+        self.0.retain(|(key, _)| key != &name);
+        self.0.push((name, value_fn));
+    }
+
+    /// Returns `true` if there are no trailers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the trailer names.
+    #[inline]
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(name, _)| name.as_ref())
+    }
+
+    /// Computes the trailer values. It should be called at the end of the stream.
+    #[inline]
+    pub fn compute(&self) -> Vec<(SharedString, String)> {
+        self.0
+            .iter()
+            .map(|(name, value_fn)| (name.clone(), value_fn()))
+            .collect()
+    }
+}
+
+impl fmt::Debug for Trailers {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
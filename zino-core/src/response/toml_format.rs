@@ -0,0 +1,96 @@
+use crate::{bail, error::Error, JsonValue, TomlValue};
+use toml::Table;
+
+/// Serializes the JSON value as a TOML document.
+///
+/// The value should be an object, since TOML can not represent a top-level array
+/// or scalar. The `null` values are omitted because TOML has no counterpart.
+pub(super) fn to_toml(value: &JsonValue) -> Result<Vec<u8>, Error> {
+    let JsonValue::Object(map) = value else {
+        bail!("the TOML document should be a table");
+    };
+    let mut table = Table::new();
+    for (key, value) in map {
+        if let Some(value) = to_toml_value(value)? {
+            table.insert(key.to_owned(), value);
+        }
+    }
+    #[cfg(feature = "toml")]
+    {
+        Ok(toml::to_string(&table)?.into_bytes())
+    }
+    #[cfg(not(feature = "toml"))]
+    {
+        drop(table);
+        bail!("the `toml` feature should be enabled to serialize the TOML document");
+    }
+}
+
+/// Converts the JSON value to a TOML value. Returns `None` for the `null` value.
+fn to_toml_value(value: &JsonValue) -> Result<Option<TomlValue>, Error> {
+    let value = match value {
+        JsonValue::Null => return Ok(None),
+        JsonValue::Bool(b) => TomlValue::Boolean(*b),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                TomlValue::Integer(i)
+            } else if let Some(f) = n.as_f64() {
+                TomlValue::Float(f)
+            } else {
+                bail!("the number `{}` can not be represented in TOML", n);
+            }
+        }
+        JsonValue::String(s) => TomlValue::String(s.to_owned()),
+        JsonValue::Array(vec) => {
+            let mut array = Vec::with_capacity(vec.len());
+            for value in vec {
+                if let Some(value) = to_toml_value(value)? {
+                    array.push(value);
+                }
+            }
+            TomlValue::Array(array)
+        }
+        JsonValue::Object(map) => {
+            let mut table = Table::new();
+            for (key, value) in map {
+                if let Some(value) = to_toml_value(value)? {
+                    table.insert(key.to_owned(), value);
+                }
+            }
+            TomlValue::Table(table)
+        }
+    };
+    Ok(Some(value))
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod tests {
+    use super::to_toml;
+    use crate::{extension::TomlValueExt, TomlValue};
+
+    #[test]
+    fn it_serializes_json_as_toml() {
+        let value = serde_json::json!({
+            "name": "zino",
+            "version": 7,
+            "ratio": 0.5,
+            "tags": ["web", "orm"],
+            "owner": { "name": "alice", "email": null },
+            "license": null,
+        });
+        let toml = String::from_utf8(to_toml(&value).unwrap()).unwrap();
+        let table = toml.parse::<TomlValue>().unwrap();
+        assert_eq!(
+            table.to_json_value(),
+            serde_json::json!({
+                "name": "zino",
+                "version": 7,
+                "ratio": 0.5,
+                "tags": ["web", "orm"],
+                "owner": { "name": "alice" },
+            })
+        );
+        assert!(to_toml(&serde_json::json!([1, 2])).is_err());
+        assert!(to_toml(&serde_json::json!(null)).is_err());
+    }
+}
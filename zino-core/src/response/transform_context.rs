@@ -0,0 +1,40 @@
+/// Context information available when transforming the response data.
+#[derive(Debug, Clone, Copy)]
+pub struct TransformContext<'a> {
+    /// Content type of the response.
+    content_type: &'a str,
+    /// Negotiated locale.
+    locale: Option<&'a str>,
+    /// Indicates the response is successful or not.
+    success: bool,
+}
+
+impl<'a> TransformContext<'a> {
+    /// Creates a new instance.
+    #[inline]
+    pub fn new(content_type: &'a str, locale: Option<&'a str>, success: bool) -> Self {
+        Self {
+            content_type,
+            locale,
+            success,
+        }
+    }
+
+    /// Returns the content type of the response.
+    #[inline]
+    pub fn content_type(&self) -> &'a str {
+        self.content_type
+    }
+
+    /// Returns the negotiated locale.
+    #[inline]
+    pub fn locale(&self) -> Option<&'a str> {
+        self.locale
+    }
+
+    /// Returns `true` if the response is successful or `false` otherwise.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.success
+    }
+}
@@ -1,22 +1,34 @@
 use crate::{
     application::http_client,
     bail,
+    datetime::DateTime,
+    encoding::hex,
     error::Error,
     extension::{HeaderMapExt, JsonObjectExt, JsonValueExt, TomlTableExt, TomlValueExt},
     helper,
     trace::TraceContext,
-    JsonValue, Map,
+    JsonValue, Map, Uuid,
 };
+use futures_timer::Delay;
+use hmac::{Hmac, Mac};
 use http::{
     header::{HeaderMap, HeaderName},
     Method,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::value::RawValue;
+use sha2::Sha256;
+use std::time::Duration;
 use toml::Table;
 use url::Url;
 
 /// User-defined HTTP callbacks.
+///
+/// If a signing secret is configured, the request is signed with a header
+/// in the form of `t={timestamp},v1={signature}`, where the signature is the hex-encoded
+/// HMAC-SHA256 of `{timestamp}.{body}` over the exact body sent.
+/// Every delivery carries an `idempotency-key` header, which stays the same
+/// across the retries, and an `x-webhook-attempt` header with the attempt count.
 pub struct WebHook {
     /// Webhook name.
     name: String,
@@ -30,6 +42,14 @@ pub struct WebHook {
     body: Option<Box<RawValue>>,
     /// Optional request params.
     params: Option<Map>,
+    /// Secret for signing the request.
+    signing_secret: Option<Vec<u8>>,
+    /// Header name of the signature.
+    signature_header: String,
+    /// Max number of the delivery attempts.
+    max_attempts: u32,
+    /// Base delay between the retries, which is doubled after each attempt.
+    retry_backoff: Duration,
 }
 
 impl WebHook {
@@ -63,6 +83,16 @@ impl WebHook {
         let params = config
             .get("params")
             .and_then(|v| v.to_json_value().into_map_opt());
+        let signing_secret = config
+            .get_str("signing-secret")
+            .map(|secret| secret.as_bytes().to_vec());
+        let signature_header = config
+            .get_str("signature-header")
+            .unwrap_or("x-webhook-signature");
+        let max_attempts = config.get_u32("max-attempts").unwrap_or(1);
+        let retry_backoff = config
+            .get_duration("retry-backoff")
+            .unwrap_or_else(|| Duration::from_secs(1));
         Ok(Self {
             name: name.to_owned(),
             method,
@@ -70,6 +100,10 @@ impl WebHook {
             headers,
             body,
             params,
+            signing_secret,
+            signature_header: signature_header.to_owned(),
+            max_attempts,
+            retry_backoff,
         })
     }
 
@@ -106,6 +140,53 @@ impl WebHook {
         self.params = params.into().into_map_opt();
     }
 
+    /// Sets the secret for signing the request.
+    #[inline]
+    pub fn set_signing_secret(&mut self, secret: impl Into<Vec<u8>>) {
+        self.signing_secret = Some(secret.into());
+    }
+
+    /// Sets the header name of the signature. The default value is `x-webhook-signature`.
+    #[inline]
+    pub fn set_signature_header(&mut self, header: impl Into<String>) {
+        self.signature_header = header.into();
+    }
+
+    /// Sets the max number of the delivery attempts and the base delay between the retries.
+    /// The request is retried for the connection errors and the `429` or `5xx` responses.
+    #[inline]
+    pub fn set_retry(&mut self, max_attempts: u32, backoff: Duration) {
+        self.max_attempts = max_attempts;
+        self.retry_backoff = backoff;
+    }
+
+    /// Computes the signature header value of the request body with the timestamp in seconds.
+    /// Returns `None` if the signing secret has not been set.
+    #[inline]
+    pub fn sign(&self, timestamp: i64) -> Option<String> {
+        let body = self.format_body();
+        self.sign_payload(body.as_deref().unwrap_or_default(), timestamp)
+    }
+
+    /// Signs the payload with the timestamp.
+    fn sign_payload(&self, body: &str, timestamp: i64) -> Option<String> {
+        let secret = self.signing_secret.as_deref()?;
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(secret).expect("HMAC can take key of any size");
+        mac.update(format!("{timestamp}.").as_bytes());
+        mac.update(body.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+        Some(format!("t={timestamp},v1={signature}"))
+    }
+
+    /// Formats the request body with the params.
+    fn format_body(&self) -> Option<String> {
+        let params = self.params.as_ref();
+        self.body
+            .as_deref()
+            .map(|v| helper::format_query(v.get(), params).into_owned())
+    }
+
     /// Returns the webhook name.
     #[inline]
     pub fn name(&self) -> &str {
@@ -116,9 +197,10 @@ impl WebHook {
     pub async fn trigger<T: DeserializeOwned>(&self) -> Result<T, Error> {
         let params = self.params.as_ref();
         let resource = helper::format_query(self.base_url.as_str(), params);
+        let body = self.format_body();
         let mut options = Map::from_entry("method", self.method.as_str());
-        if let Some(body) = self.body.as_deref().map(|v| v.get()) {
-            options.upsert("body", helper::format_query(body, params));
+        if let Some(body) = body.as_deref() {
+            options.upsert("body", body);
         }
 
         let mut headers = HeaderMap::new();
@@ -133,19 +215,59 @@ impl WebHook {
             }
         }
 
-        let mut trace_context = TraceContext::new();
-        let span_id = trace_context.span_id();
-        trace_context
-            .trace_state_mut()
-            .push("zino", format!("{span_id:x}"));
-
-        let response = http_client::request_builder(resource.as_ref(), Some(&options))?
-            .headers(headers)
-            .header("traceparent", trace_context.traceparent())
-            .header("tracestate", trace_context.tracestate())
-            .send()
-            .await?
-            .error_for_status()?;
+        if !headers.contains_key("idempotency-key") {
+            if let Ok(idempotency_key) = Uuid::now_v7().to_string().parse() {
+                headers.insert("idempotency-key", idempotency_key);
+            }
+        }
+
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+        let response = loop {
+            let mut trace_context = TraceContext::new();
+            let span_id = trace_context.span_id();
+            trace_context
+                .trace_state_mut()
+                .push("zino", format!("{span_id:x}"));
+
+            let mut request_builder =
+                http_client::request_builder(resource.as_ref(), Some(&options))?
+                    .headers(headers.clone())
+                    .header("traceparent", trace_context.traceparent())
+                    .header("tracestate", trace_context.tracestate())
+                    .header("x-webhook-attempt", attempt);
+            let timestamp = DateTime::now().timestamp();
+            if let Some(signature) =
+                self.sign_payload(body.as_deref().unwrap_or_default(), timestamp)
+            {
+                request_builder = request_builder.header(&self.signature_header, signature);
+            }
+            match request_builder.send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable = status.is_server_error() || status.as_u16() == 429;
+                    if !retryable || attempt >= max_attempts {
+                        break response.error_for_status()?;
+                    }
+                    tracing::warn!(
+                        webhook = self.name,
+                        attempt,
+                        status_code = status.as_u16(),
+                        "fail to deliver the webhook"
+                    );
+                }
+                Err(err) if attempt < max_attempts => {
+                    tracing::warn!(
+                        webhook = self.name,
+                        attempt,
+                        "fail to deliver the webhook: {err}"
+                    );
+                }
+                Err(err) => return Err(err.into()),
+            }
+            Delay::new(self.retry_backoff * 2u32.saturating_pow(attempt - 1)).await;
+            attempt += 1;
+        };
         let data = if response.headers().has_json_content_type() {
             response.json().await?
         } else {
@@ -155,3 +277,36 @@ impl WebHook {
         Ok(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::WebHook;
+    use toml::Table;
+
+    #[test]
+    fn it_signs_webhook_body() {
+        let config = r#"
+            base-url = "https://example.com/hooks"
+            signing-secret = "whsec"
+            [body]
+            event = "order.created"
+        "#
+        .parse::<Table>()
+        .unwrap();
+        let mut webhook = WebHook::try_new(&config).unwrap();
+        let signature = webhook.sign(1700000000).unwrap();
+        let (timestamp, signature) = signature.split_once(',').unwrap();
+        assert_eq!(timestamp, "t=1700000000");
+        assert!(signature.starts_with("v1=") && signature.len() == 67);
+        assert_eq!(
+            webhook.sign(1700000000).unwrap().split_once(',').unwrap().1,
+            signature
+        );
+
+        webhook.set_signing_secret("other");
+        assert_ne!(
+            webhook.sign(1700000000).unwrap().split_once(',').unwrap().1,
+            signature
+        );
+    }
+}
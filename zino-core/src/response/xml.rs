@@ -0,0 +1,115 @@
+use crate::JsonValue;
+use std::io::{self, Write};
+
+/// Serializes the JSON value as an XML document with the root element into the writer.
+///
+/// Object keys become elements, arrays become repeated elements with the same name,
+/// and a top-level scalar is wrapped in the root element. The items of
+/// a top-level array are wrapped as the `item` elements of the root element.
+pub(super) fn to_xml<W: Write>(value: &JsonValue, root: &str, mut writer: W) -> io::Result<W> {
+    let root = element_name(root);
+    writer.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    if value.is_array() {
+        write!(writer, "<{root}>")?;
+        write_element(&mut writer, "item", value)?;
+        write!(writer, "</{root}>")?;
+    } else {
+        write_element(&mut writer, &root, value)?;
+    }
+    Ok(writer)
+}
+
+/// Writes the value as an element.
+fn write_element<W: Write>(xml: &mut W, name: &str, value: &JsonValue) -> io::Result<()> {
+    match value {
+        JsonValue::Array(vec) => {
+            for value in vec {
+                write_element(xml, name, value)?;
+            }
+        }
+        JsonValue::Null => write!(xml, "<{name}/>")?,
+        _ => {
+            write!(xml, "<{name}>")?;
+            match value {
+                JsonValue::Object(map) => {
+                    for (key, value) in map {
+                        write_element(xml, &element_name(key), value)?;
+                    }
+                }
+                JsonValue::String(s) => escape_text(xml, s)?,
+                _ => write!(xml, "{value}")?,
+            }
+            write!(xml, "</{name}>")?;
+        }
+    }
+    Ok(())
+}
+
+/// Escapes the text content.
+fn escape_text<W: Write>(xml: &mut W, text: &str) -> io::Result<()> {
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        let escaped = match c {
+            '&' => "&amp;",
+            '<' => "&lt;",
+            '>' => "&gt;",
+            '"' => "&quot;",
+            '\'' => "&apos;",
+            _ => continue,
+        };
+        xml.write_all(&text.as_bytes()[start..index])?;
+        xml.write_all(escaped.as_bytes())?;
+        start = index + c.len_utf8();
+    }
+    xml.write_all(&text.as_bytes()[start..])
+}
+
+/// Converts the key into a valid element name by replacing the invalid characters with `_`.
+fn element_name(key: &str) -> String {
+    let mut name = key
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let starts_with_letter = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_');
+    if !starts_with_letter || name.to_ascii_lowercase().starts_with("xml") {
+        name.insert(0, '_');
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_xml;
+
+    #[test]
+    fn it_serializes_json_as_xml() {
+        let value = serde_json::json!({
+            "1st": null,
+            "name": "A & B",
+            "tags": ["x", "y"],
+        });
+        let xml = String::from_utf8(to_xml(&value, "order", Vec::new()).unwrap()).unwrap();
+        assert_eq!(
+            xml,
+            r#"<?xml version="1.0" encoding="UTF-8"?><order><_1st/><name>A &amp; B</name><tags>x</tags><tags>y</tags></order>"#
+        );
+
+        let xml =
+            String::from_utf8(to_xml(&serde_json::json!(42), "root", Vec::new()).unwrap()).unwrap();
+        assert!(xml.ends_with("<root>42</root>"));
+
+        let xml =
+            String::from_utf8(to_xml(&serde_json::json!([1, 2]), "root", Vec::new()).unwrap())
+                .unwrap();
+        assert!(xml.ends_with("<root><item>1</item><item>2</item></root>"));
+    }
+}
@@ -2,10 +2,14 @@
 
 mod server_timing;
 mod timing_metric;
+mod timing_span;
 mod trace_context;
 mod trace_state;
 
 pub use server_timing::ServerTiming;
 pub use timing_metric::TimingMetric;
+pub use timing_span::TimingSpan;
+
+pub(crate) use timing_span::TimingBuffer;
 pub use trace_context::TraceContext;
 pub use trace_state::TraceState;
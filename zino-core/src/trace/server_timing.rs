@@ -8,6 +8,10 @@ use std::fmt;
 pub struct ServerTiming {
     /// Server timing metrics.
     metrics: SmallVec<[TimingMetric; 4]>,
+    /// Number of decimal places for the durations in milliseconds.
+    precision: Option<usize>,
+    /// A flag to aggregate the durations of the metrics with the same name.
+    aggregated: bool,
 }
 
 impl ServerTiming {
@@ -16,14 +20,43 @@ impl ServerTiming {
     pub fn new() -> Self {
         Self {
             metrics: SmallVec::new(),
+            precision: None,
+            aggregated: false,
         }
     }
 
-    /// Pushes an entry into the list of metrics.
+    /// Sets the number of decimal places for the durations in milliseconds.
+    /// By default, the shortest representation in microseconds is used.
     #[inline]
+    pub fn set_precision(&mut self, precision: usize) {
+        self.precision = Some(precision);
+    }
+
+    /// Enables or disables the aggregation of the metrics with the same name.
+    /// If enabled, the durations are summed up in the first entry.
+    #[inline]
+    pub fn set_aggregated(&mut self, aggregated: bool) {
+        self.aggregated = aggregated;
+    }
+
+    /// Pushes an entry into the list of metrics.
     pub fn push(&mut self, metric: TimingMetric) {
+        if self.aggregated {
+            if let Some(entry) = self.metrics.iter_mut().find(|m| m.name() == metric.name()) {
+                if let Some(duration) = metric.duration() {
+                    entry.add_duration(duration);
+                }
+                return;
+            }
+        }
         self.metrics.push(metric);
     }
+
+    /// Returns the list of metrics.
+    #[inline]
+    pub fn metrics(&self) -> &[TimingMetric] {
+        &self.metrics
+    }
 }
 
 impl Default for ServerTiming {
@@ -34,12 +67,33 @@ impl Default for ServerTiming {
 
 impl fmt::Display for ServerTiming {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = self
-            .metrics
-            .iter()
-            .map(|metric| metric.to_string())
-            .collect::<Vec<_>>()
-            .join(", ");
-        write!(f, "{output}")
+        for (index, metric) in self.metrics.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            metric.format(f, self.precision)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServerTiming;
+    use crate::trace::TimingMetric;
+    use std::time::Duration;
+
+    #[test]
+    fn it_formats_server_timing() {
+        let mut server_timing = ServerTiming::new();
+        server_timing.set_precision(1);
+        server_timing.set_aggregated(true);
+        for millis in [2, 3] {
+            let duration = Duration::from_millis(millis);
+            server_timing.push(TimingMetric::new("db".into(), None, Some(duration)));
+        }
+        server_timing.push(TimingMetric::new("miss".into(), None, None));
+        assert_eq!(server_timing.metrics().len(), 2);
+        assert_eq!(server_timing.to_string(), "db;dur=5.0, miss");
     }
 }
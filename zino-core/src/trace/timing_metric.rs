@@ -45,28 +45,64 @@ impl TimingMetric {
         let duration = self.duration;
         (duration > Duration::ZERO).then_some(duration)
     }
-}
 
-impl fmt::Display for TimingMetric {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let name = self.name();
+    /// Adds the duration to the timing duration.
+    #[inline]
+    pub(super) fn add_duration(&mut self, duration: Duration) {
+        self.duration += duration;
+    }
+
+    /// Formats the metric with the number of decimal places for the duration in milliseconds.
+    /// If the precision is `None`, the shortest representation in microseconds is used.
+    ///
+    /// The invalid characters of the name are replaced with `_`, and the description
+    /// is emitted as a quoted string if it is not a token.
+    pub(super) fn format(&self, f: &mut fmt::Formatter, precision: Option<usize>) -> fmt::Result {
+        let name = self
+            .name()
+            .chars()
+            .map(|c| if is_token_char(c) { c } else { '_' })
+            .collect::<String>();
+        f.write_str(&name)?;
+        if let Some(description) = self.description() {
+            if description.chars().all(is_token_char) && !description.is_empty() {
+                write!(f, ";desc={description}")?;
+            } else {
+                let description = description
+                    .chars()
+                    .filter(|c| !c.is_control())
+                    .collect::<String>()
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"");
+                write!(f, ";desc=\"{description}\"")?;
+            }
+        }
         if let Some(duration) = self.duration() {
-            let mut buffer = ryu::Buffer::new();
-            let millis = (duration.as_micros() as f64) / 1000.0;
-            let duration_millis = buffer.format_finite(millis);
-            if let Some(description) = self.description() {
-                write!(f, "{name};desc={description};dur={duration_millis}")
+            if let Some(precision) = precision {
+                let millis = duration.as_secs_f64() * 1000.0;
+                write!(f, ";dur={millis:.precision$}")?;
             } else {
-                write!(f, "{name};dur={duration_millis}")
+                let mut buffer = ryu::Buffer::new();
+                let millis = (duration.as_micros() as f64) / 1000.0;
+                write!(f, ";dur={}", buffer.format_finite(millis))?;
             }
-        } else if let Some(description) = self.description() {
-            write!(f, "{name};desc={description}")
-        } else {
-            write!(f, "{name}")
         }
+        Ok(())
     }
 }
 
+impl fmt::Display for TimingMetric {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f, None)
+    }
+}
+
+/// Returns `true` if the character is a `tchar` defined in RFC 9110.
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
 #[cfg(test)]
 mod tests {
     use super::TimingMetric;
@@ -87,5 +123,15 @@ mod tests {
         let total_timing_metric =
             TimingMetric::new("total".into(), None, Some(Duration::from_secs_f64(0.01082)));
         assert_eq!(format!("{total_timing_metric}"), "total;dur=10.82");
+
+        let metric = TimingMetric::new(
+            "db query".into(),
+            Some("SELECT \"user\"".into()),
+            Some(Duration::from_secs_f64(0.0024635)),
+        );
+        assert_eq!(
+            format!("{metric}"),
+            r#"db_query;desc="SELECT \"user\"";dur=2.463"#
+        );
     }
 }
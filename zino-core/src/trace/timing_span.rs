@@ -0,0 +1,83 @@
+use crate::{trace::TimingMetric, SharedString};
+use parking_lot::Mutex;
+use std::{sync::Arc, time::Instant};
+
+/// A shared buffer of the timing metrics recorded by the spans.
+pub(crate) type TimingBuffer = Arc<Mutex<Vec<TimingMetric>>>;
+
+/// A guard which records a server timing metric with the elapsed time when dropped.
+///
+/// The metric is pushed into a buffer shared with the response instead of
+/// borrowing it, so that multiple spans can coexist and outlive the borrow.
+#[derive(Debug)]
+#[must_use = "the span records the elapsed time when it is dropped"]
+pub struct TimingSpan {
+    /// Metric name.
+    name: SharedString,
+    /// Optional description.
+    description: Option<SharedString>,
+    /// Start time.
+    start_time: Instant,
+    /// Buffer shared with the response.
+    buffer: TimingBuffer,
+}
+
+impl TimingSpan {
+    /// Creates a new instance which records into the buffer.
+    #[inline]
+    pub(crate) fn new(name: SharedString, buffer: TimingBuffer) -> Self {
+        Self {
+            name,
+            description: None,
+            start_time: Instant::now(),
+            buffer,
+        }
+    }
+
+    /// Sets the description of the metric.
+    #[inline]
+    pub fn set_description(&mut self, description: impl Into<SharedString>) {
+        self.description = Some(description.into());
+    }
+
+    /// Returns the metric name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// Consumes `self` and records the metric immediately.
+    #[inline]
+    pub fn finish(self) {}
+}
+
+impl Drop for TimingSpan {
+    fn drop(&mut self) {
+        let duration = self.start_time.elapsed();
+        let metric = TimingMetric::new(self.name.clone(), self.description.take(), Some(duration));
+        self.buffer.lock().push(metric);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimingBuffer, TimingSpan};
+
+    #[test]
+    fn it_records_timing_span_on_drop() {
+        let buffer = TimingBuffer::default();
+        let db_span = TimingSpan::new("db".into(), buffer.clone());
+        {
+            let mut cache_span = TimingSpan::new("cache".into(), buffer.clone());
+            cache_span.set_description("miss");
+        }
+        db_span.finish();
+
+        let metrics = buffer.lock();
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].name(), "cache");
+        assert_eq!(metrics[0].description(), Some("miss"));
+        assert_eq!(metrics[1].name(), "db");
+        assert!(metrics[1].duration().is_some());
+    }
+}
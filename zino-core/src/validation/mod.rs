@@ -24,9 +24,12 @@ pub use validator::PhoneNumberValidator;
 pub use validator::RegexValidator;
 
 /// A record of validation results.
+///
+/// Each failed entry can have an optional machine-readable error code,
+/// such as `required`, `too_long` or `invalid_format`.
 #[derive(Debug, Default)]
 pub struct Validation {
-    failed_entries: SmallVec<[(SharedString, Error); 4]>,
+    failed_entries: SmallVec<[(SharedString, Error, Option<SharedString>); 4]>,
 }
 
 impl Validation {
@@ -42,7 +45,7 @@ impl Validation {
     #[inline]
     pub fn from_entry(key: impl Into<SharedString>, err: impl Into<Error>) -> Self {
         let mut entries = SmallVec::new();
-        entries.push((key.into(), err.into()));
+        entries.push((key.into(), err.into(), None));
         Self {
             failed_entries: entries,
         }
@@ -51,13 +54,51 @@ impl Validation {
     /// Records an entry with the supplied message.
     #[inline]
     pub fn record(&mut self, key: impl Into<SharedString>, message: impl Into<SharedString>) {
-        self.failed_entries.push((key.into(), Error::new(message)));
+        self.failed_entries
+            .push((key.into(), Error::new(message), None));
+    }
+
+    /// Records an entry with the error code and the supplied message.
+    #[inline]
+    pub fn record_with_code(
+        &mut self,
+        key: impl Into<SharedString>,
+        code: impl Into<SharedString>,
+        message: impl Into<SharedString>,
+    ) {
+        self.failed_entries
+            .push((key.into(), Error::new(message), Some(code.into())));
     }
 
     /// Records an entry for the error.
     #[inline]
     pub fn record_fail(&mut self, key: impl Into<SharedString>, err: impl Into<Error>) {
-        self.failed_entries.push((key.into(), err.into()));
+        self.failed_entries.push((key.into(), err.into(), None));
+    }
+
+    /// Records an entry for the error with the error code.
+    #[inline]
+    pub fn record_fail_with_code(
+        &mut self,
+        key: impl Into<SharedString>,
+        code: impl Into<SharedString>,
+        err: impl Into<Error>,
+    ) {
+        self.failed_entries
+            .push((key.into(), err.into(), Some(code.into())));
+    }
+
+    /// Records the keys in the json object which are not contained in the known fields.
+    pub fn check_unknown_fields(&mut self, data: &Map, fields: &[&str]) {
+        for key in data.keys() {
+            if !fields.contains(&key.as_str()) {
+                self.failed_entries.push((
+                    key.clone().into(),
+                    Error::new("it is an unknown field"),
+                    Some("unknown_field".into()),
+                ));
+            }
+        }
     }
 
     /// Validates the string value with a specific format.
@@ -65,136 +106,136 @@ impl Validation {
         match format {
             "alphabetic" => {
                 if let Err(err) = AlphabeticValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "alphanumeric" => {
                 if let Err(err) = AlphanumericValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ascii" => {
                 if let Err(err) = AsciiValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ascii-alphabetic" => {
                 if let Err(err) = AsciiAlphabeticValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ascii-alphanumeric" => {
                 if let Err(err) = AsciiAlphanumericValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ascii-digit" => {
                 if let Err(err) = AsciiDigitValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ascii-hexdigit" => {
                 if let Err(err) = AsciiHexdigitValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ascii-lowercase" => {
                 if let Err(err) = AsciiLowercaseValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ascii-uppercase" => {
                 if let Err(err) = AsciiUppercaseValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             #[cfg(feature = "validator-credit-card")]
             "credit-card" => {
                 if let Err(err) = CreditCardValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "date" => {
                 if let Err(err) = DateValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "date-time" => {
                 if let Err(err) = DateTimeValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             #[cfg(feature = "validator-email")]
             "email" => {
                 if let Err(err) = EmailValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "host" => {
                 if let Err(err) = HostValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "hostname" => {
                 if let Err(err) = HostnameValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ip" => {
                 if let Err(err) = IpAddrValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ipv4" => {
                 if let Err(err) = Ipv4AddrValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "ipv6" => {
                 if let Err(err) = Ipv6AddrValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "lowercase" => {
                 if let Err(err) = LowercaseValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "numeric" => {
                 if let Err(err) = NumericValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             #[cfg(feature = "validator-phone-number")]
             "phone-number" => {
                 if let Err(err) = PhoneNumberValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             #[cfg(feature = "validator-regex")]
             "regex" => {
                 if let Err(err) = RegexValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "time" => {
                 if let Err(err) = TimeValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "uppercase" => {
                 if let Err(err) = UppercaseValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "uri" => {
                 if let Err(err) = UriValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             "uuid" => {
                 if let Err(err) = UuidValidator.validate(value) {
-                    self.record_fail(key, err);
+                    self.record_fail_with_code(key, "invalid_format", err);
                 }
             }
             _ => {
@@ -207,7 +248,16 @@ impl Validation {
     /// Returns true if the validation contains a value for the specified key.
     #[inline]
     pub fn contains_key(&self, key: &str) -> bool {
-        self.failed_entries.iter().any(|(field, _)| field == key)
+        self.failed_entries.iter().any(|(field, ..)| field == key)
+    }
+
+    /// Returns the error code for the specified key.
+    #[inline]
+    pub fn error_code(&self, key: &str) -> Option<&str> {
+        self.failed_entries
+            .iter()
+            .find(|(field, ..)| field == key)
+            .and_then(|(_, _, code)| code.as_deref())
     }
 
     /// Returns `true` if the validation is success.
@@ -225,11 +275,27 @@ impl Validation {
             .collect()
     }
 
+    /// Returns a list of invalid params with the `name`, `reason` and optional `code`.
+    pub fn invalid_params_detail(&self) -> Vec<Map> {
+        self.failed_entries
+            .iter()
+            .map(|(key, err, code)| {
+                let mut param = Map::new();
+                param.upsert("name", key.as_ref());
+                param.upsert("reason", err.message());
+                if let Some(code) = code {
+                    param.upsert("code", code.as_ref());
+                }
+                param
+            })
+            .collect()
+    }
+
     /// Consumes the validation and returns as a json object.
     #[must_use]
     pub fn into_map(self) -> Map {
         let mut map = Map::new();
-        for (key, err) in self.failed_entries {
+        for (key, err, _) in self.failed_entries {
             let message = err.message();
             tracing::warn!("invalid value for `{key}`: {message}");
             map.upsert(key, message);
@@ -243,7 +309,7 @@ impl fmt::Display for Validation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let failed_entries = &self.failed_entries;
         let mut errors = Vec::with_capacity(failed_entries.len());
-        for (key, err) in failed_entries {
+        for (key, err, _) in failed_entries {
             let message = format!("invalid value for `{key}`: {}", err.message());
             errors.push(message);
         }
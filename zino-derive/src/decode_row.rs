@@ -42,6 +42,10 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 decode_model_fields.push(quote! {
                     model.#ident = orm::decode_decimal(row, #name)?;
                 });
+            } else if type_name == "Option<(f64, f64)>" {
+                decode_model_fields.push(quote! {
+                    model.#ident = orm::decode_point(row, #name)?;
+                });
             } else if type_name == "Map" {
                 decode_model_fields.push(quote! {
                     if let JsonValue::Object(map) = orm::decode(row, #name)? {
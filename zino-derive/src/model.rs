@@ -25,6 +25,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     // Parsing struct attributes
     let mut item_name = "entry".to_owned();
     let mut item_name_plural = "entries".to_owned();
+    let mut strict_mode = None;
     for attr in input.attrs.iter() {
         for (key, value) in parser::parse_schema_attr(attr).into_iter() {
             if let Some(value) = value {
@@ -38,6 +39,9 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                     "item_name_plural" => {
                         item_name_plural = value;
                     }
+                    "strict_mode" => {
+                        strict_mode = value.parse::<bool>().ok();
+                    }
                     _ => (),
                 }
             }
@@ -47,10 +51,12 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     // Parsing field attributes
     let mut field_constructors = Vec::new();
     let mut field_setters = Vec::new();
+    let mut known_fields = Vec::new();
     for field in parser::parse_struct_fields(input.data) {
         let type_name = parser::get_type_name(&field.ty);
         if let Some(ident) = field.ident {
             let name = ident.to_string();
+            known_fields.push(name.clone());
             let mut enable_setter = true;
             let mut is_inherent = false;
             for attr in field.attrs.iter() {
@@ -319,6 +325,14 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
             model
         }
     };
+    let model_strict_mode = strict_mode.map(|strict_mode| {
+        quote! {
+            #[inline]
+            fn strict_mode() -> bool {
+                #strict_mode
+            }
+        }
+    });
     quote! {
         use zino_core::validation::Validation;
 
@@ -331,12 +345,17 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 #model_constructor
             }
 
+            #model_strict_mode
+
             #[must_use]
             fn read_map(&mut self, data: &Map) -> Validation {
                 let mut validation = Validation::new();
                 if data.is_empty() {
                     validation.record("data", "should be nonempty");
                 } else {
+                    if Self::strict_mode() {
+                        validation.check_unknown_fields(data, &[#(#known_fields),*]);
+                    }
                     #(#field_setters)*
                 }
                 validation
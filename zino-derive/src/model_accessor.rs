@@ -33,7 +33,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                     composite_constraints.push(quote! {
                         let columns = vec![#(#column_values),*];
                         if !self.is_unique_on(columns).await? {
-                            validation.record(#composite_field, "the composite values should be unique");
+                            validation.record_with_code(#composite_field, "not_unique", "the composite values should be unique");
                         }
                     });
                 }
@@ -58,6 +58,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
     let mut primary_key_name = String::from("id");
     let mut model_references: HashMap<String, Vec<String>> = HashMap::new();
     let mut populated_field_mappings: HashMap<String, String> = HashMap::new();
+    let mut polymorphic_fetches = Vec::new();
     for field in parser::parse_struct_fields(input.data) {
         let type_name = parser::get_type_name(&field.ty);
         if let Some(ident) = field.ident {
@@ -67,6 +68,11 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 let type_name = type_name.as_str();
                 let arguments = parser::parse_schema_attr(attr);
                 let is_readable = arguments.iter().all(|arg| arg.0 != "write_only");
+                let reference_models = arguments
+                    .iter()
+                    .find_map(|arg| (arg.0 == "reference_models").then(|| arg.1.clone()))
+                    .flatten()
+                    .unwrap_or_default();
                 for (key, value) in arguments.into_iter() {
                     match key.as_str() {
                         "alias" => {
@@ -102,6 +108,82 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                             }
                             snapshot_fields.push(field);
                         }
+                        "reference_type" => {
+                            let target_id = match type_name {
+                                "Uuid" => Some(quote! {
+                                    (!self.#ident.is_nil()).then(|| self.#ident.to_string())
+                                }),
+                                "String" => Some(quote! {
+                                    (!self.#ident.is_empty()).then(|| self.#ident.clone())
+                                }),
+                                "Option<Uuid>" => Some(quote! {
+                                    self.#ident.filter(|v| !v.is_nil()).map(|v| v.to_string())
+                                }),
+                                "Option<String>" => Some(quote! {
+                                    self.#ident.clone().filter(|v| !v.is_empty())
+                                }),
+                                _ => None,
+                            };
+                            if let (Some(target_id), Some(type_field)) = (target_id, value) {
+                                let type_ident = format_ident!("{}", type_field);
+                                let mut constraint_branches = Vec::new();
+                                let mut fetch_branches = Vec::new();
+                                for model in reference_models.split(',') {
+                                    let model = model.trim();
+                                    if model.is_empty() {
+                                        continue;
+                                    }
+                                    let model_ident = format_ident!("{}", model);
+                                    constraint_branches.push(quote! {
+                                        if target_type == <#model_ident>::MODEL_NAME {
+                                            let data = <#model_ident>::filter(vec![target_id.clone()]).await?;
+                                            if data.len() != 1 {
+                                                validation.record_with_code(#name, "nonexistent", "it is a nonexistent value");
+                                            }
+                                            matched = true;
+                                        }
+                                    });
+                                    fetch_branches.push(quote! {
+                                        if target_type == <#model_ident>::MODEL_NAME {
+                                            let mut query = <#model_ident>::default_query();
+                                            query.add_filter(<#model_ident>::PRIMARY_KEY_NAME, target_id.clone());
+                                            let model = <#model_ident>::find_one::<ZinoMap>(&query).await?;
+                                            if let Some(mut model) = model {
+                                                <#model_ident>::after_decode(&mut model).await?;
+                                                <#model_ident>::translate_model(&mut model);
+                                                return Ok(Some(model));
+                                            }
+                                            return Ok(None);
+                                        }
+                                    });
+                                }
+                                if constraint_branches.is_empty() {
+                                    continue;
+                                }
+                                field_constraints.push(quote! {
+                                    if let Some(target_id) = #target_id {
+                                        let target_type = self.#type_ident.as_str();
+                                        let mut matched = false;
+                                        #(#constraint_branches)*
+                                        if target_type.is_empty() {
+                                            validation.record_with_code(#type_field, "required", "it should be nonempty");
+                                        } else if !matched {
+                                            let message = format!("the model `{target_type}` is not allowed");
+                                            validation.record_with_code(#type_field, "not_allowed", message);
+                                        }
+                                    }
+                                });
+                                polymorphic_fetches.push(quote! {
+                                    if field == #name {
+                                        if let Some(target_id) = #target_id {
+                                            let target_type = self.#type_ident.as_str();
+                                            #(#fetch_branches)*
+                                        }
+                                        return Ok(None);
+                                    }
+                                });
+                            }
+                        }
                         "reference" => {
                             if let Some(value) = value {
                                 let model_ident = format_ident!("{}", value);
@@ -110,7 +192,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         let values = vec![self.#ident.to_string()];
                                         let data = <#model_ident>::filter(values).await?;
                                         if data.len() != 1 {
-                                            validation.record(#name, "it is a nonexistent value");
+                                            validation.record_with_code(#name, "nonexistent", "it is a nonexistent value");
                                         }
                                     });
                                 } else if matches!(type_name, "Option<Uuid>" | "Option<String>") {
@@ -119,7 +201,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                                 let values = vec![value.to_string()];
                                                 let data = <#model_ident>::filter(values).await?;
                                                 if data.len() != 1 {
-                                                    validation.record(#name, "it is a nonexistent value");
+                                                    validation.record_with_code(#name, "nonexistent", "it is a nonexistent value");
                                                 }
                                             }
                                         });
@@ -133,7 +215,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             if length > 0 {
                                                 let data = <#model_ident>::filter(values).await?;
                                                 if data.len() != length {
-                                                    validation.record(#name, "there are nonexistent values");
+                                                    validation.record_with_code(#name, "nonexistent", "there are nonexistent values");
                                                 }
                                             }
                                         });
@@ -144,7 +226,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             if length > 0 {
                                                 let data = <#model_ident>::filter(values).await?;
                                                 if data.len() != length {
-                                                    validation.record(#name, "there are nonexistent values");
+                                                    validation.record_with_code(#name, "nonexistent", "there are nonexistent values");
                                                 }
                                             }
                                         });
@@ -154,7 +236,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                                 let values = vec![value.clone()];
                                                 let data = <#model_ident>::filter(values).await?;
                                                 if data.len() != 1 {
-                                                    validation.record(#name, "it is a nonexistent value");
+                                                    validation.record_with_code(#name, "nonexistent", "it is a nonexistent value");
                                                 }
                                             }
                                         });
@@ -163,7 +245,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         let values = vec![self.#ident.clone()];
                                         let data = <#model_ident>::filter(values).await?;
                                         if data.len() != 1 {
-                                            validation.record(#name, "it is a nonexistent value");
+                                            validation.record_with_code(#name, "nonexistent", "it is a nonexistent value");
                                         }
                                     });
                                 }
@@ -205,7 +287,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let columns = vec![(#name, value.to_string().into())];
                                             if !self.is_unique_on(columns).await? {
                                                 let message = format!("the value `{value}` is not unique");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "not_unique", message);
                                             }
                                         }
                                     });
@@ -216,7 +298,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let columns = vec![(#name, value.into())];
                                             if !self.is_unique_on(columns).await? {
                                                 let message = format!("the value `{value}` is not unique");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "not_unique", message);
                                             }
                                         }
                                     });
@@ -226,7 +308,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let columns = vec![(#name, value.into())];
                                             if !self.is_unique_on(columns).await? {
                                                 let message = format!("the value `{value}` is not unique");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "not_unique", message);
                                             }
                                         }
                                     });
@@ -236,7 +318,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let columns = vec![(#name, value.to_string().into())];
                                             if !self.is_unique_on(columns).await? {
                                                 let message = format!("the value `{value}` is not unique");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "not_unique", message);
                                             }
                                         }
                                     });
@@ -246,7 +328,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let columns = vec![(#name, value.into())];
                                             if !self.is_unique_on(columns).await? {
                                                 let message = format!("the value `{value}` is not unique");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "not_unique", message);
                                             }
                                         }
                                     });
@@ -256,7 +338,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                     let columns = vec![(#name, value.into())];
                                     if !self.is_unique_on(columns).await? {
                                         let message = format!("the value `{value}` is not unique");
-                                        validation.record(#name, message);
+                                        validation.record_with_code(#name, "not_unique", message);
                                     }
                                 });
                             }
@@ -265,13 +347,13 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                             if type_name == "String" {
                                 field_constraints.push(quote! {
                                     if self.#ident.is_empty() {
-                                        validation.record(#name, "it should be nonempty");
+                                        validation.record_with_code(#name, "required", "it should be nonempty");
                                     }
                                 });
                             } else if type_name == "Uuid" {
                                 field_constraints.push(quote! {
                                     if self.#ident.is_nil() {
-                                        validation.record(#name, "it should not be nil");
+                                        validation.record_with_code(#name, "required", "it should not be nil");
                                     }
                                 });
                             }
@@ -282,7 +364,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                             {
                                 field_constraints.push(quote! {
                                     if self.#ident.is_empty() {
-                                        validation.record(#name, "it should be nonempty");
+                                        validation.record_with_code(#name, "required", "it should be nonempty");
                                     }
                                 });
                             }
@@ -296,7 +378,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             if !self.#ident.is_empty() {
                                                 let validator = <#validator_ident>::#validator_fn_ident();
                                                 if let Err(err) = validator.validate(self.#ident.as_str()) {
-                                                    validation.record_fail(#name, err);
+                                                    validation.record_fail_with_code(#name, "invalid_format", err);
                                                 }
                                             }
                                         });
@@ -305,7 +387,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                     field_constraints.push(quote! {
                                             if !self.#ident.is_empty() {
                                                 if let Err(err) = #validator_ident.validate(self.#ident.as_str()) {
-                                                    validation.record_fail(#name, err);
+                                                    validation.record_fail_with_code(#name, "invalid_format", err);
                                                 }
                                             }
                                         });
@@ -331,7 +413,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                                 let value = self.#ident.as_str();
                                                 if !values.contains(&value) {
                                                     let message = format!("the value `{value}` is not allowed");
-                                                    validation.record(#name, message);
+                                                    validation.record_with_code(#name, "not_allowed", message);
                                                 }
                                             }
                                         });
@@ -341,7 +423,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             for value in self.#ident.iter() {
                                                 if !values.contains(&value.as_str()) {
                                                     let message = format!("the value `{value}` is not allowed");
-                                                    validation.record(#name, message);
+                                                    validation.record_with_code(#name, "not_allowed", message);
                                                     break;
                                                 }
                                             }
@@ -358,7 +440,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                     let length = #length;
                                     if self.#ident.len() != length {
                                         let message = format!("the length should be {length}");
-                                        validation.record(#name, message);
+                                        validation.record_with_code(#name, "invalid_length", message);
                                     }
                                 });
                             } else if type_name == "Option<String>" {
@@ -366,7 +448,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                     let length = #length;
                                     if let Some(ref s) = self.#ident && s.len() != length {
                                         let message = format!("the length should be {length}");
-                                        validation.record(#name, message);
+                                        validation.record_with_code(#name, "invalid_length", message);
                                     }
                                 });
                             }
@@ -380,7 +462,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         let length = #length;
                                         if self.#ident.len() > length {
                                             let message = format!("the length should be at most {length}");
-                                            validation.record(#name, message);
+                                            validation.record_with_code(#name, "too_long", message);
                                         }
                                     });
                             } else if type_name == "Option<String>" {
@@ -388,7 +470,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         let length = #length;
                                         if let Some(ref s) = self.#ident && s.len() > length {
                                             let message = format!("the length should be at most {length}");
-                                            validation.record(#name, message);
+                                            validation.record_with_code(#name, "too_long", message);
                                         }
                                     });
                             }
@@ -402,7 +484,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         let length = #length;
                                         if self.#ident.len() < length {
                                             let message = format!("the length should be at least {length}");
-                                            validation.record(#name, message);
+                                            validation.record_with_code(#name, "too_short", message);
                                         }
                                     });
                             } else if type_name == "Option<String>" {
@@ -410,7 +492,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         let length = #length;
                                         if let Some(ref s) = self.#ident && s.len() < length {
                                             let message = format!("the length should be at least {length}");
-                                            validation.record(#name, message);
+                                            validation.record_with_code(#name, "too_short", message);
                                         }
                                     });
                             }
@@ -422,7 +504,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         let length = #length;
                                         if self.#ident.len() > length {
                                             let message = format!("the length should be at most {length}");
-                                            validation.record(#name, message);
+                                            validation.record_with_code(#name, "too_many_items", message);
                                         }
                                     });
                                 }
@@ -435,7 +517,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                         let length = #length;
                                         if self.#ident.len() < length {
                                             let message = format!("the length should be at least {length}");
-                                            validation.record(#name, message);
+                                            validation.record_with_code(#name, "too_few_items", message);
                                         }
                                     });
                                 }
@@ -448,7 +530,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                     for index in 1..slice.len() {
                                         if slice[index..].contains(&slice[index - 1]) {
                                             let message = format!("array items should be unique");
-                                            validation.record(#name, message);
+                                            validation.record_with_code(#name, "duplicate_items", message);
                                             break;
                                         }
                                     }
@@ -464,7 +546,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let field_value = <#field_type_ident>::#field_type_fn_ident();
                                             if self.#ident >= field_value {
                                                 let message = format!("should be less than `{field_value}`");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "too_large", message);
                                             }
                                         });
                                 } else {
@@ -473,7 +555,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let field_value = self.#field_ident;
                                             if self.#ident >= field_value {
                                                 let message = format!("should be less than `{field_value}`");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "too_large", message);
                                             }
                                         });
                                 }
@@ -488,7 +570,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let field_value = <#field_type_ident>::#field_type_fn_ident();
                                             if self.#ident <= field_value {
                                                 let message = format!("should be greater than `{field_value}`");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "too_small", message);
                                             }
                                         });
                                 } else {
@@ -497,7 +579,7 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                                             let field_value = self.#field_ident;
                                             if self.#ident <= field_value {
                                                 let message = format!("should be greater than `{field_value}`");
-                                                validation.record(#name, message);
+                                                validation.record_with_code(#name, "too_small", message);
                                             }
                                         });
                                 }
@@ -771,6 +853,10 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 let mut updates = self.next_edition_updates();
                 #(#soft_delete_updates)*
                 mutation.append_updates(&mut updates);
+                if Self::has_column("deleted_at") {
+                    let deleted_at = zino_core::datetime::DateTime::now().to_utc_timestamp();
+                    mutation.add_read_only_update("deleted_at", deleted_at);
+                }
                 mutation
             }
 
@@ -829,6 +915,14 @@ pub(super) fn parse_token_stream(input: DeriveInput) -> TokenStream {
                 #(#fetched_one_queries)*
             }
 
+            async fn fetch_polymorphic_reference(
+                &self,
+                field: &str,
+            ) -> Result<Option<ZinoMap>, ZinoError> {
+                #(#polymorphic_fetches)*
+                Ok(None)
+            }
+
             async fn random_associations() -> Result<ZinoMap, Error> {
                 let mut associations = ZinoMap::new();
                 #(#sample_queries)*
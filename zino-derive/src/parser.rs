@@ -47,6 +47,9 @@ pub(super) fn get_type_name(ty: &Type) -> String {
             }
             return type_name;
         }
+    } else if let Type::Tuple(ty) = ty {
+        let type_names = ty.elems.iter().map(get_type_name).collect::<Vec<_>>();
+        return "(".to_owned() + &type_names.join(", ") + ")";
     }
     String::new()
 }
@@ -1,53 +1,176 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
 use dioxus::prelude::*;
-use zino_core::{json, JsonValue, SharedString};
+use std::time::Duration;
+use zino_core::{extension::JsonObjectExt, json, JsonValue, Map, SharedString};
 
 /// A ToastUI Editor.
 pub fn TuiEditor(props: TuiEditorProps) -> Element {
-    let mut markdown = use_signal(String::new);
-    let eval_editor = eval(
-        r#"
-        const { Editor } = toastui;
-        const { codeSyntaxHighlight } = Editor.plugin;
-
-        let options = await dioxus.recv();
-        options.el = document.getElementById(options.id);
-        options.plugins = [codeSyntaxHighlight];
-        options.events = {
-            change: function() {
-                document.getElementById("tui-editor-input").value = tuiEditor.getMarkdown();
-            },
-        };
-        const tuiEditor = new Editor(options);
-        tuiEditor.show();
-        "#,
-    );
-    spawn(async move {
-        loop {
-            let mut eval = eval(
-                r#"
-                const value = document.getElementById("tui-editor-input").value;
-                dioxus.send(value);
-                "#,
-            );
-            if let Ok(JsonValue::String(s)) = eval.recv().await {
-                if markdown() != s {
-                    if let Some(handler) = props.on_change.as_ref() {
-                        handler.call(s.clone());
+    let mut markdown = use_signal(|| props.content.to_string());
+    let input_id = format!("{}-input", props.id);
+    let eval_editor = use_hook(|| {
+        eval(
+            r#"
+            const { Editor } = toastui;
+
+            let options = await dioxus.recv();
+            const input = document.getElementById(options.id + "-input");
+            const pendingUploads = new Map();
+            let uploadId = 0;
+            if (options.imageUpload) {
+                options.hooks = {
+                    addImageBlobHook: async function(blob, callback) {
+                        const id = ++uploadId;
+                        // The bytes are base64-encoded to keep the message compact.
+                        const data = await new Promise((resolve, reject) => {
+                            const reader = new FileReader();
+                            reader.onload = () => resolve(reader.result.split(",", 2)[1] || "");
+                            reader.onerror = () => reject(reader.error);
+                            reader.readAsDataURL(blob);
+                        });
+                        pendingUploads.set(id, callback);
+                        dioxus.send({ id, name: blob.name || "image", type: blob.type, data });
+                    },
+                };
+            }
+            delete options.imageUpload;
+            options.el = document.getElementById(options.id);
+            options.plugins = options.plugins
+                .map((name) => Editor.plugin[name])
+                .filter((plugin) => plugin);
+            options.events = {
+                change: function() {
+                    markdown = tuiEditor.getMarkdown();
+                    input.value = markdown;
+                },
+            };
+
+            // The viewer is mounted instead of the editor in the read-only mode.
+            let markdown = options.initialValue;
+            const createEditor = function(readonly) {
+                const editor = Editor.factory({ ...options, viewer: readonly, initialValue: markdown });
+                if (!readonly) {
+                    editor.show();
+                }
+                return editor;
+            };
+            let readonly = options.readonly;
+            let tuiEditor = createEditor(readonly);
+            while (true) {
+                const message = await dioxus.recv();
+                if (message.action === "readonly") {
+                    if (message.readonly !== readonly) {
+                        readonly = message.readonly;
+                        tuiEditor.destroy();
+                        tuiEditor = createEditor(readonly);
                     }
-                    markdown.set(s);
+                } else if (message.action === "content") {
+                    // The change event is suppressed by the caller for the external changes.
+                    markdown = message.content;
+                    input.value = markdown;
+                    tuiEditor.setMarkdown(markdown, false);
+                } else if (message.action === "upload") {
+                    const callback = pendingUploads.get(message.id);
+                    if (callback) {
+                        pendingUploads.delete(message.id);
+                        if (message.url) {
+                            callback(message.url, message.alt || "");
+                        }
+                    }
+                }
+            }
+            "#,
+        )
+    });
+    let on_image_upload = props.on_image_upload;
+    use_future(move || async move {
+        let mut eval_editor = eval_editor;
+        while let Ok(message) = eval_editor.recv().await {
+            if let (Some(handler), JsonValue::Object(message)) = (on_image_upload, message) {
+                if let Some(upload) = ImageUpload::from_message(&message, eval_editor) {
+                    handler.call(upload);
                 }
             }
         }
     });
+    let mut readonly = use_signal(|| props.readonly);
+    use_effect(use_reactive((&props.content,), move |(content,)| {
+        // The signal is updated before the editor so that the change is not echoed back.
+        if *markdown.peek() != content.as_ref() {
+            markdown.set(content.to_string());
+            eval_editor
+                .send(json!({ "action": "content", "content": content }))
+                .ok();
+        }
+    }));
+    let poll_input_id = input_id.clone();
+    let debounce = Duration::from_millis(props.debounce);
+    let on_change = props.on_change;
+    let mut poll_input = use_future(move || {
+        let input_id = poll_input_id.clone();
+        async move {
+            // The future is cancelled when the component is unmounted or becomes read-only,
+            // and it is restarted when the component becomes editable.
+            if *readonly.peek() {
+                return;
+            }
+            let mut pending_value = None;
+            loop {
+                tokio::time::sleep(debounce).await;
+                let mut eval = eval(
+                    r#"
+                    const id = await dioxus.recv();
+                    const input = document.getElementById(id);
+                    dioxus.send(input ? input.value : null);
+                    "#,
+                );
+                if eval.send(input_id.as_str().into()).is_err() {
+                    continue;
+                }
+                if let Ok(JsonValue::String(s)) = eval.recv().await {
+                    if markdown() == s {
+                        pending_value = None;
+                    } else if pending_value.as_ref() == Some(&s) {
+                        // The value has been stable for a debounce interval.
+                        if let Some(handler) = on_change.as_ref() {
+                            handler.call(s.clone());
+                        }
+                        markdown.set(s);
+                        pending_value = None;
+                    } else {
+                        pending_value = Some(s);
+                    }
+                }
+            }
+        }
+    });
+    use_effect(use_reactive((&props.readonly,), move |(value,)| {
+        if *readonly.peek() != value {
+            readonly.set(value);
+            eval_editor
+                .send(json!({ "action": "readonly", "readonly": value }))
+                .ok();
+            if value {
+                poll_input.cancel();
+            } else {
+                poll_input.restart();
+            }
+        }
+    }));
     rsx! {
         input {
-            id: "tui-editor-input",
+            id: "{input_id}",
             r#type: "hidden",
+            value: "{props.content}",
         }
         div {
             id: "{props.id}",
             onmounted: move |_event| {
-                let options = json!({
+                let plugins = props
+                    .plugins
+                    .iter()
+                    .map(|plugin| plugin.as_str())
+                    .collect::<Vec<_>>();
+                let mut options = json!({
                     "id": props.id,
                     "height": props.height,
                     "minHeight": props.min_height,
@@ -57,9 +180,25 @@ pub fn TuiEditor(props: TuiEditorProps) -> Element {
                     "language": props.locale,
                     "theme": props.theme,
                     "referenceDefinition": true,
+                    "imageUpload": props.on_image_upload.is_some(),
+                    "readonly": readonly(),
                     "usageStatistics": false,
+                    "plugins": plugins,
                 });
+                if let Some(toolbar_items) = props.toolbar_items.as_ref() {
+                    options["toolbarItems"] = json!(toolbar_items);
+                }
                 eval_editor.send(options).ok();
+                if let Some(handler) = props.on_change.as_ref() {
+                    handler.call(markdown());
+                }
+                if let Some(handler) = props.on_ready.as_ref() {
+                    handler.call(TuiEditorHandle {
+                        input_id: input_id.clone(),
+                        eval: eval_editor,
+                        markdown,
+                    });
+                }
             }
         }
     }
@@ -71,7 +210,7 @@ pub struct TuiEditorProps {
     /// The editor ID.
     #[props(into, default = "editor".into())]
     pub id: SharedString,
-    /// The initial value of Markdown string.
+    /// The value of Markdown string, which is set into the editor when it is changed externally.
     #[props(into)]
     pub content: SharedString,
     /// The height of the container.
@@ -92,6 +231,172 @@ pub struct TuiEditorProps {
     /// The i18n locale.
     #[props(into, default = "en-US".into())]
     pub locale: SharedString,
-    /// An event handler to be called when the input value is changed.
+    /// An event handler to be called with the Markdown string when it is changed.
+    /// It is also called with the initial value on mount.
     pub on_change: Option<EventHandler<String>>,
+    /// An event handler to be called with a handle to control the editor after it is mounted.
+    pub on_ready: Option<EventHandler<TuiEditorHandle>>,
+    /// The debounce interval in milliseconds for the change event.
+    #[props(default = 300)]
+    pub debounce: u64,
+    /// The toolbar items grouped by the dividers. The default toolbar is used if it is `None`.
+    pub toolbar_items: Option<Vec<Vec<SharedString>>>,
+    /// The bundled plugins to be loaded.
+    #[props(default = vec![EditorPlugin::CodeSyntaxHighlight])]
+    pub plugins: Vec<EditorPlugin>,
+    /// A flag to determine whether the content is read-only.
+    /// The viewer is mounted instead of the editor if it is `true`.
+    #[props(default)]
+    pub readonly: bool,
+    /// An event handler to be called when an image is pasted or dropped.
+    /// The image is not embedded as a data URI if it is set.
+    pub on_image_upload: Option<EventHandler<ImageUpload>>,
+}
+
+/// A handle to get or set the content of the [`TuiEditor`] imperatively.
+#[derive(Clone)]
+pub struct TuiEditorHandle {
+    /// The ID of the hidden input.
+    input_id: String,
+    /// The channel to the editor.
+    eval: UseEval,
+    /// The current Markdown string.
+    markdown: Signal<String>,
+}
+
+impl TuiEditorHandle {
+    /// Gets the latest Markdown string in the editor, without waiting for the change event.
+    pub async fn get_markdown(&self) -> Option<String> {
+        let mut eval = eval(
+            r#"
+            const id = await dioxus.recv();
+            const input = document.getElementById(id);
+            dioxus.send(input ? input.value : null);
+            "#,
+        );
+        eval.send(self.input_id.as_str().into()).ok()?;
+        match eval.recv().await {
+            Ok(JsonValue::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Sets the Markdown string in the editor. The `on_change` handler is not called.
+    pub fn set_markdown(&self, markdown: impl Into<String>) {
+        let markdown = markdown.into();
+        let mut signal = self.markdown;
+        if *signal.peek() != markdown {
+            self.eval
+                .send(json!({ "action": "content", "content": markdown }))
+                .ok();
+            signal.set(markdown);
+        }
+    }
+}
+
+/// The bundled plugins of the [`TuiEditor`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorPlugin {
+    /// The `chart` plugin.
+    Chart,
+    /// The `code-syntax-highlight` plugin.
+    CodeSyntaxHighlight,
+    /// The `color-syntax` plugin.
+    ColorSyntax,
+    /// The `table-merged-cell` plugin.
+    TableMergedCell,
+    /// The `uml` plugin.
+    Uml,
+}
+
+impl EditorPlugin {
+    /// Returns the name of the plugin in `toastui.Editor.plugin`.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chart => "chart",
+            Self::CodeSyntaxHighlight => "codeSyntaxHighlight",
+            Self::ColorSyntax => "colorSyntax",
+            Self::TableMergedCell => "tableMergedCell",
+            Self::Uml => "uml",
+        }
+    }
+}
+
+/// An image to be uploaded from the [`TuiEditor`].
+///
+/// The uploaded URL should be sent back by [`ImageUpload::insert()`],
+/// so that the editor inserts the image into the Markdown string.
+#[derive(Clone)]
+pub struct ImageUpload {
+    /// The upload ID.
+    id: u64,
+    /// The file name.
+    name: String,
+    /// The MIME type.
+    mime_type: String,
+    /// The image bytes.
+    data: Vec<u8>,
+    /// The channel to the editor.
+    eval: UseEval,
+}
+
+impl ImageUpload {
+    /// Parses the message sent by the editor.
+    fn from_message(message: &Map, eval: UseEval) -> Option<Self> {
+        let id = message.get_u64("id")?;
+        let data = STANDARD.decode(message.get_str("data")?).ok()?;
+        Some(Self {
+            id,
+            name: message.get_str("name").unwrap_or("image").to_owned(),
+            mime_type: message.get_str("type").unwrap_or_default().to_owned(),
+            data,
+            eval,
+        })
+    }
+
+    /// Returns the file name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the MIME type.
+    #[inline]
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// Returns the image bytes.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consumes `self` and returns the image bytes.
+    #[inline]
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Inserts the uploaded image URL with an optional alt text into the editor.
+    #[inline]
+    pub fn insert(&self, url: &str, alt: Option<&str>) {
+        let message = json!({
+            "action": "upload",
+            "id": self.id,
+            "url": url,
+            "alt": alt.unwrap_or(&self.name),
+        });
+        self.eval.send(message).ok();
+    }
+
+    /// Cancels the upload without inserting anything into the editor.
+    #[inline]
+    pub fn cancel(&self) {
+        self.eval
+            .send(json!({ "action": "upload", "id": self.id }))
+            .ok();
+    }
 }
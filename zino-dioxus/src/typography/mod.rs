@@ -7,7 +7,7 @@ mod span;
 mod tag;
 
 pub use card::{Card, CardProps};
-pub use editor::{TuiEditor, TuiEditorProps};
+pub use editor::{EditorPlugin, ImageUpload, TuiEditor, TuiEditorHandle, TuiEditorProps};
 pub use markdown::{Markdown, MarkdownProps};
 pub use span::{FixedWidthSpan, FixedWidthSpanProps};
 pub use tag::{Tag, TagProps, Tags, TagsProps};
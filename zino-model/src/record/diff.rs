@@ -0,0 +1,180 @@
+use zino_core::{extension::JsonObjectExt, JsonValue, Map};
+
+/// Computes a structured diff from the old map to the new map.
+///
+/// The diff has the `added`, `removed` and `changed` objects keyed by the JSON Pointer paths
+/// (RFC 6901), where a changed entry has the `old` and `new` values. The `~` and `/`
+/// in a key are escaped as `~0` and `~1`, so that a key is never ambiguous with a nested path. Nested objects are diffed
+/// recursively, and the other values including arrays are reported as replacements.
+pub(super) fn diff_maps(old: &Map, new: &Map) -> Map {
+    let mut added = Map::new();
+    let mut removed = Map::new();
+    let mut changed = Map::new();
+    diff_entries("", old, new, &mut added, &mut removed, &mut changed);
+
+    let mut diff = Map::new();
+    diff.upsert("added", added);
+    diff.upsert("removed", removed);
+    diff.upsert("changed", changed);
+    diff
+}
+
+/// Diffs the entries of the maps with the path prefix.
+fn diff_entries(
+    prefix: &str,
+    old: &Map,
+    new: &Map,
+    added: &mut Map,
+    removed: &mut Map,
+    changed: &mut Map,
+) {
+    for (key, old_value) in old {
+        let path = [prefix, "/", &escape_segment(key)].concat();
+        match new.get(key) {
+            Some(new_value) => match (old_value, new_value) {
+                (JsonValue::Object(old), JsonValue::Object(new)) => {
+                    diff_entries(&path, old, new, added, removed, changed);
+                }
+                _ if old_value != new_value => {
+                    let mut entry = Map::new();
+                    entry.upsert("old", old_value.clone());
+                    entry.upsert("new", new_value.clone());
+                    changed.upsert(path, entry);
+                }
+                _ => (),
+            },
+            None => {
+                removed.upsert(path, old_value.clone());
+            }
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            added.upsert(
+                [prefix, "/", &escape_segment(key)].concat(),
+                new_value.clone(),
+            );
+        }
+    }
+}
+
+/// Forward-applies the diff produced by [`diff_maps()`] onto the map.
+pub(super) fn apply_diff(map: &mut Map, diff: &Map) {
+    if let Some(removed) = diff.get_object("removed") {
+        for path in removed.keys() {
+            if let Some((parent, key)) = lookup_parent(map, path) {
+                parent.remove(&key);
+            }
+        }
+    }
+    for (key, field) in [("added", None), ("changed", Some("new"))] {
+        if let Some(entries) = diff.get_object(key) {
+            for (path, value) in entries {
+                let value = match field {
+                    Some(field) => value.get(field).cloned().unwrap_or_default(),
+                    None => value.clone(),
+                };
+                if let Some((parent, key)) = lookup_parent(map, path) {
+                    parent.upsert(key, value);
+                }
+            }
+        }
+    }
+}
+
+/// Escapes a key as a segment of the JSON Pointer path.
+fn escape_segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Unescapes a segment of the JSON Pointer path as a key.
+fn unescape_segment(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// Looks up the parent object of the JSON Pointer path, creating the missing objects.
+fn lookup_parent<'a>(map: &'a mut Map, path: &str) -> Option<(&'a mut Map, String)> {
+    let mut parent = map;
+    let mut segments = path.strip_prefix('/')?.split('/').peekable();
+    while let Some(segment) = segments.next() {
+        let key = unescape_segment(segment);
+        if segments.peek().is_none() {
+            return Some((parent, key));
+        }
+        parent = parent
+            .entry(key)
+            .or_insert_with(|| Map::new().into())
+            .as_object_mut()?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_diff, diff_maps};
+    use zino_core::{
+        extension::{JsonObjectExt, JsonValueExt},
+        json, JsonValue, Map,
+    };
+
+    #[test]
+    fn it_diffs_and_applies_maps() {
+        let old = json!({
+            "title": "draft",
+            "tags": ["a"],
+            "meta": { "author": "alice", "draft": true },
+        });
+        let new = json!({
+            "title": "final",
+            "tags": ["a", "b"],
+            "meta": { "author": "alice", "reviewer": "bob" },
+            "published": true,
+        });
+        let (old, new) = (old.into_map_opt().unwrap(), new.into_map_opt().unwrap());
+        let diff = diff_maps(&old, &new);
+        assert_eq!(
+            JsonValue::from(diff.clone()),
+            json!({
+                "added": { "/meta/reviewer": "bob", "/published": true },
+                "removed": { "/meta/draft": true },
+                "changed": {
+                    "/tags": { "old": ["a"], "new": ["a", "b"] },
+                    "/title": { "old": "draft", "new": "final" },
+                },
+            })
+        );
+
+        let mut map = old.clone();
+        apply_diff(&mut map, &diff);
+        assert_eq!(map, new);
+        assert!(diff_maps(&new, &map)
+            .get_object("changed")
+            .unwrap()
+            .is_empty());
+        assert_eq!(diff_maps(&Map::new(), &Map::new()).len(), 3);
+    }
+
+    #[test]
+    fn it_escapes_keys_in_diff_paths() {
+        let old = json!({
+            "a.b": 1,
+            "a": { "b": 2 },
+            "x/y": { "~z": "old" },
+        });
+        let new = json!({
+            "a.b": 10,
+            "a": { "b": 2 },
+            "x/y": { "~z": "new" },
+        });
+        let (old, new) = (old.into_map_opt().unwrap(), new.into_map_opt().unwrap());
+        let diff = diff_maps(&old, &new);
+        let changed = diff.get_object("changed").unwrap();
+        assert!(changed.contains_key("/a.b"));
+        assert!(changed.contains_key("/x~1y/~0z"));
+        assert_eq!(changed.len(), 2);
+
+        let mut map = old.clone();
+        apply_diff(&mut map, &diff);
+        assert_eq!(map, new);
+    }
+}
@@ -1,31 +1,82 @@
 //! The `record` model and related services.
 
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::mem;
 use zino_core::{
+    bail,
     datetime::DateTime,
     error::Error,
     extension::JsonObjectExt,
     model::{Model, ModelHooks},
+    orm::ModelHelper,
     validation::Validation,
-    Map, Uuid,
+    JsonValue, Map, Uuid,
 };
 use zino_derive::{DecodeRow, ModelAccessor, Schema};
 
 #[cfg(any(feature = "owner-id", feature = "maintainer-id"))]
 use crate::user::User;
 
-#[cfg(feature = "maintainer-id")]
+#[cfg(any(feature = "maintainer-id", feature = "tenant-id"))]
 use zino_core::auth::UserSession;
 
+mod diff;
+mod status;
+
+pub use status::RecordStatus;
+
+#[cfg(feature = "slug")]
+mod slug;
+
+/// Format version of the record snapshot.
+const SNAPSHOT_FORMAT: u64 = 1;
+
 /// The `record` model.
+///
+/// With the `tenant-id` feature, the records are isolated by the `tenant_id` of the user session.
+/// It is set in `before_insert_check`, injected as a filter in `before_list`, and checked
+/// in `before_access` and `before_respond`, so that the default controller never crosses
+/// the tenants. A missing tenant context is rejected with `403 Forbidden`, and a row
+/// without the `tenant_id` is never responded. The scoped helpers such as `Record::near()`,
+/// `ModelAccessor::update_where()` and `Record::find_by_namespace()` take the extension
+/// and run `before_list` in the same way.
+/// The methods of `Schema` are not scoped and should be regarded as the privileged bypass.
+///
+/// With the `namespace` feature, the records can be scoped by `Record::namespace_query()`,
+/// and an empty `namespace` defaults to `Record::model_namespace()` on insert.
+///
+/// With the `geo` feature, the `location` field stores a point of `[lng, lat]`,
+/// which can be used to find the nearby records via `Record::near()`.
+/// The field is nullable, so the spatial index is not created for MySQL.
+///
+/// With the `soft-delete` feature, the `deleted_at` field is stamped when the record is
+/// logically deleted, and the default list query excludes such records unless the
+/// `show_deleted` flag is enabled. The records can be brought back by `Record::restore()`.
+///
+/// The `version` field is used for the optimistic concurrency control. A client can send
+/// the `version` it has read, and the update is rejected with an error of the context
+/// [`OptimisticLockConflict`](zino_core::orm::OptimisticLockConflict) if it is outdated.
+///
+/// The subfields of `extra` can be encrypted at rest by configuring
+/// `database.encryption.fields.record = ["extra.field"]`. They are encrypted in `before_save`
+/// and decrypted in `after_decode`, and can not be filtered unless they are listed
+/// in `database.encryption.deterministic-fields.record` instead.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, DecodeRow, Schema, ModelAccessor)]
 #[serde(default)]
+#[cfg_attr(
+    all(feature = "slug", feature = "namespace"),
+    schema(unique_on = "(slug, namespace)")
+)]
 pub struct Record {
     // Basic fields.
     #[schema(read_only)]
     id: Uuid,
-    #[schema(not_null)]
+    #[schema(not_null, index_type = "text")]
     name: String,
+    #[cfg(feature = "slug")]
+    #[cfg_attr(feature = "namespace", schema(index_type = "btree"))]
+    #[cfg_attr(not(feature = "namespace"), schema(index_type = "unique"))]
+    slug: String,
     #[cfg(feature = "namespace")]
     #[schema(default_value = "Record::model_namespace", index_type = "hash")]
     namespace: String,
@@ -34,6 +85,7 @@ pub struct Record {
     visibility: String,
     #[schema(default_value = "Active", index_type = "hash")]
     status: String,
+    #[schema(index_type = "text")]
     description: String,
 
     // Info fields.
@@ -43,6 +95,9 @@ pub struct Record {
     signature: String,
     #[schema(read_only, index_type = "btree")]
     recorded_at: DateTime,
+    #[cfg(feature = "geo")]
+    #[schema(index_type = "spatial")]
+    location: Option<(f64, f64)>, // (lng, lat)
 
     // Extensions.
     #[schema(read_only)]
@@ -55,10 +110,16 @@ pub struct Record {
     #[cfg(feature = "maintainer-id")]
     #[schema(reference = "User")]
     maintainer_id: Option<Uuid>, // user.id
+    #[cfg(feature = "tenant-id")]
+    #[schema(snapshot, read_only, not_null, index_type = "hash")]
+    tenant_id: Uuid,
     #[schema(read_only, default_value = "now", index_type = "btree")]
     created_at: DateTime,
     #[schema(default_value = "now", index_type = "btree")]
     updated_at: DateTime,
+    #[cfg(feature = "soft-delete")]
+    #[schema(read_only)]
+    deleted_at: Option<DateTime>,
     version: u64,
     #[cfg(feature = "edition")]
     edition: u32,
@@ -77,6 +138,9 @@ impl Model for Record {
 
     fn read_map(&mut self, data: &Map) -> Validation {
         let mut validation = Validation::new();
+        if Self::strict_mode() {
+            validation.check_unknown_fields(data, Self::fields());
+        }
         if let Some(result) = data.parse_uuid("id") {
             match result {
                 Ok(id) => self.id = id,
@@ -86,9 +150,45 @@ impl Model for Record {
         if let Some(name) = data.parse_string("name") {
             self.name = name.into_owned();
         }
+        #[cfg(feature = "slug")]
+        if let Some(slug) = data.parse_string("slug") {
+            let slug = slug::slugify(&slug);
+            if slug.is_empty() {
+                validation.record("slug", "should contain at least one alphanumeric character");
+            } else {
+                self.slug = slug;
+            }
+        }
         if let Some(description) = data.parse_string("description") {
             self.description = description.into_owned();
         }
+        if let Some(result) = data.parse_u64("version") {
+            match result {
+                Ok(version) => self.version = version,
+                Err(err) => validation.record_fail("version", err),
+            }
+        }
+        #[cfg(feature = "geo")]
+        match data.get("location") {
+            Some(JsonValue::Array(point)) => {
+                let lng = point.first().and_then(|v| v.as_f64());
+                let lat = point.get(1).and_then(|v| v.as_f64());
+                if let (2, Some(lng), Some(lat)) = (point.len(), lng, lat) {
+                    if !(-180.0..=180.0).contains(&lng) {
+                        validation.record("location", "longitude should be in [-180, 180]");
+                    } else if !(-90.0..=90.0).contains(&lat) {
+                        validation.record("location", "latitude should be in [-90, 90]");
+                    } else {
+                        self.location = Some((lng, lat));
+                    }
+                } else {
+                    validation.record("location", "should be a point of `[lng, lat]`");
+                }
+            }
+            Some(JsonValue::Null) => self.location = None,
+            Some(_) => validation.record("location", "should be a point of `[lng, lat]`"),
+            None => {}
+        }
         #[cfg(feature = "owner-id")]
         if let Some(result) = data.parse_uuid("owner_id") {
             match result {
@@ -107,13 +207,605 @@ impl Model for Record {
     }
 }
 
+impl Record {
+    /// Exports the record as a json object, including the read-only fields
+    /// such as `integrity`, `signature` and the timestamps.
+    ///
+    /// # Note
+    ///
+    /// It is intended for backup and data migration between environments.
+    /// The output should not be returned to untrusted clients directly.
+    #[inline]
+    pub fn export(&self) -> Map {
+        self.clone().into_map()
+    }
+
+    /// Takes a snapshot of the record's full state as a self-describing json object,
+    /// which has the `model`, `format`, `id`, `version` and `taken_at` entries,
+    /// and the exported fields in the `data` object.
+    ///
+    /// The format is stable enough to be stored in a separate history table,
+    /// and can be rehydrated by [`restore_from()`](Self::restore_from).
+    pub fn snapshot(&self) -> Map {
+        let mut snapshot = Map::new();
+        snapshot.upsert("model", Self::MODEL_NAME);
+        snapshot.upsert("format", SNAPSHOT_FORMAT);
+        snapshot.upsert("id", self.id.to_string());
+        snapshot.upsert("version", self.version);
+        #[cfg(feature = "edition")]
+        snapshot.upsert("edition", self.edition);
+        snapshot.upsert("taken_at", DateTime::now().to_string());
+        snapshot.upsert("data", self.export());
+        snapshot
+    }
+
+    /// Restores the record's state from a snapshot produced by [`snapshot()`](Self::snapshot).
+    ///
+    /// The writable fields are restored by [`read_map()`](Model::read_map),
+    /// together with the `status`, `namespace`, `visibility` and `extra` fields.
+    /// The `id`, `created_at` and `tenant_id` fields are never restored.
+    /// The `version`, `edition` and `updated_at` fields are bumped from the current state,
+    /// the `recorded_at` field is re-stamped, and the `integrity` and `signature` fields
+    /// are recomputed by [`seal()`](Self::seal).
+    ///
+    /// # Note
+    ///
+    /// The record is left unchanged if the validation fails. The restored state
+    /// can be written to the table by [`restore_snapshot()`](Self::restore_snapshot).
+    pub fn restore_from(&mut self, snapshot: &Map) -> Validation {
+        let mut validation = Validation::new();
+        if snapshot.get_str("model") != Some(Self::MODEL_NAME) {
+            validation.record("model", "should be the snapshot of a record");
+        }
+        if snapshot.get_u64("format") != Some(SNAPSHOT_FORMAT) {
+            validation.record("format", "should be a supported snapshot format");
+        }
+        match snapshot.parse_uuid("id") {
+            Some(Ok(id)) if id == self.id => {}
+            Some(Err(err)) => validation.record_fail("id", err),
+            _ => validation.record("id", "should be the same as the record to be restored"),
+        }
+        let Some(data) = snapshot.get_object("data") else {
+            validation.record("data", "should be an object");
+            return validation;
+        };
+        if !validation.is_success() {
+            return validation;
+        }
+
+        let mut record = self.clone();
+        let mut writable_data = data.clone();
+        for field in ["id", "version"] {
+            writable_data.remove(field);
+        }
+        let validation = record.read_map(&writable_data);
+        if !validation.is_success() {
+            return validation;
+        }
+        if let Some(status) = data.get_str("status") {
+            record.status = status.to_owned();
+        }
+        #[cfg(feature = "namespace")]
+        if let Some(namespace) = data.get_str("namespace") {
+            record.set_namespace(namespace);
+        }
+        #[cfg(feature = "visibility")]
+        if let Some(visibility) = data.get_str("visibility") {
+            record.visibility = visibility.to_owned();
+        }
+        if let Some(extra) = data.get_object("extra") {
+            record.extra = extra.clone();
+        }
+
+        let now = DateTime::now();
+        record.version = self.version + 1;
+        #[cfg(feature = "edition")]
+        {
+            record.edition = self.edition + 1;
+        }
+        record.updated_at = now;
+        record.recorded_at = now;
+        record.seal();
+        *self = record;
+        validation
+    }
+
+    /// Restores the record from a snapshot as [`restore_from()`](Self::restore_from) does,
+    /// and writes the restored state to the table by [`state_mutation()`](Self::state_mutation).
+    ///
+    /// The update is rejected with an error of the context
+    /// [`OptimisticLockConflict`](zino_core::orm::OptimisticLockConflict) if the record
+    /// is outdated, and the record is reloaded from the table on success.
+    /// The validation is returned without any writes if it fails.
+    pub async fn restore_snapshot(&mut self, snapshot: &Map) -> Result<Validation, Error> {
+        let mut record = self.clone();
+        let validation = record.restore_from(snapshot);
+        if validation.is_success() {
+            self.write_state(&record).await?;
+        }
+        Ok(validation)
+    }
+
+    /// Persists the changes of the `extra` content made in memory, such as the ones by
+    /// [`apply_diff()`](Self::apply_diff), [`set_content_field()`](Self::set_content_field)
+    /// and [`merge_content()`](Self::merge_content).
+    ///
+    /// The `integrity` and `signature` fields are recomputed by [`seal()`](Self::seal),
+    /// and the `version`, `edition` and `updated_at` fields are bumped. The update is
+    /// rejected with an error of the context
+    /// [`OptimisticLockConflict`](zino_core::orm::OptimisticLockConflict) if the record
+    /// is outdated, and the record is reloaded from the table on success.
+    pub async fn save_content(&mut self) -> Result<(), Error> {
+        let mut record = self.clone();
+        record.version = self.version + 1;
+        #[cfg(feature = "edition")]
+        {
+            record.edition = self.edition + 1;
+        }
+        record.updated_at = DateTime::now();
+        record.seal();
+        self.write_state(&record).await
+    }
+
+    /// Constructs a `Mutation` for writing the state of the record by
+    /// [`update_one()`](zino_core::orm::Schema::update_one).
+    ///
+    /// Besides the writable fields, it updates the read-only `extra`, `integrity`,
+    /// `signature` and `recorded_at` fields, which are skipped by
+    /// [`Schema::update()`](zino_core::orm::Schema::update).
+    /// The fields of `extra` are encrypted in the same way as `before_save`.
+    pub fn state_mutation(&self) -> Result<Mutation, Error> {
+        let mut data = self.clone().into_map();
+        Self::encrypt_fields(&mut data)?;
+
+        let mut mutation = Self::default_mutation();
+        for field in ["extra", "integrity", "signature", "recorded_at"] {
+            if let Some(value) = data.remove(field) {
+                mutation.add_read_only_update(field, value);
+            }
+        }
+        mutation.append_updates(&mut data);
+        Ok(mutation)
+    }
+
+    /// Writes the next state of the record to the table and reloads the record.
+    async fn write_state(&mut self, record: &Record) -> Result<(), Error> {
+        let query = self.current_version_query();
+        let mut mutation = record.state_mutation()?;
+        let ctx = Self::update_one(&query, &mut mutation).await?;
+        self.version_conflict().check(&ctx)?;
+        *self = Self::try_get_model(&self.id).await?;
+        Ok(())
+    }
+
+    /// Returns a strong ETag derived from the `id`, `version` and `updated_at` fields,
+    /// such as `"0190c1d2-...-3-18f7a2b3c4d"`.
+    ///
+    /// The `version` is bumped by every effective change, so that the ETag identifies
+    /// a unique revision of the record and can be compared strongly by `If-Match`.
+    /// It can be used as the `etag` header consumed by
+    /// [`Response::evaluate_preconditions()`](zino_core::response::Response::evaluate_preconditions).
+    #[inline]
+    pub fn etag(&self) -> String {
+        let timestamp = self.updated_at.timestamp_millis();
+        format!("\"{}-{}-{:x}\"", self.id, self.version, timestamp)
+    }
+
+    /// Returns a structured diff of the `extra` content from `self` to the other record,
+    /// which has the `added`, `removed` and `changed` objects keyed by the JSON Pointer paths
+    /// such as `/meta/author`. A changed entry has the `old` and `new` values.
+    ///
+    /// It can be used to render the changelog between two editions of a record.
+    #[inline]
+    pub fn diff_content(&self, other: &Record) -> Map {
+        diff::diff_maps(&self.extra, &other.extra)
+    }
+
+    /// Forward-applies a diff produced by [`diff_content()`](Self::diff_content)
+    /// onto the `extra` content. The change can be persisted by
+    /// [`save_content()`](Self::save_content).
+    #[inline]
+    pub fn apply_diff(&mut self, diff: &Map) {
+        diff::apply_diff(&mut self.extra, diff);
+    }
+
+    /// Gets a field of the `extra` content and deserializes it as an instance of type `T`.
+    /// Returns `None` if the field does not exist or is `null`, and an error naming
+    /// the field if it has an unexpected shape.
+    pub fn content_field<T: DeserializeOwned>(&self, key: &str) -> Option<Result<T, Error>> {
+        let value = self.extra.get(key).filter(|v| !v.is_null())?;
+        let result = serde_json::from_value(value.clone()).map_err(|err| {
+            let message = format!("invalid shape of the content field `{key}`");
+            Error::with_source(message, err)
+        });
+        Some(result)
+    }
+
+    /// Sets a field of the `extra` content with the serialized value.
+    ///
+    /// The value is rejected if the key is empty, if it fails to be serialized,
+    /// or if it changes the JSON type of an existing non-null field.
+    /// The field is set in memory and can be persisted by [`save_content()`](Self::save_content).
+    pub fn set_content_field<T: Serialize>(&mut self, key: &str, value: &T) -> Validation {
+        let mut validation = Validation::new();
+        if key.is_empty() {
+            validation.record("extra", "the key of a content field should be nonempty");
+            return validation;
+        }
+        match serde_json::to_value(value) {
+            Ok(value) => {
+                let existing_type = self
+                    .extra
+                    .get(key)
+                    .filter(|v| !v.is_null())
+                    .map(json_type_name);
+                match existing_type {
+                    Some(expected) if !value.is_null() && json_type_name(&value) != expected => {
+                        validation.record(key.to_owned(), format!("should be {expected}"));
+                    }
+                    _ => {
+                        self.extra.upsert(key, value);
+                    }
+                }
+            }
+            Err(err) => validation.record_fail(key.to_owned(), err),
+        }
+        validation
+    }
+
+    /// Merges the data into the `extra` content without clobbering the whole map.
+    /// The nested objects are merged recursively, and the other values are upserted.
+    /// The merged content can be persisted by [`save_content()`](Self::save_content).
+    #[inline]
+    pub fn merge_content(&mut self, data: &Map) {
+        merge_maps(&mut self.extra, data);
+    }
+
+    /// Sets the `status` field.
+    #[inline]
+    pub fn set_status(&mut self, status: RecordStatus) {
+        self.status = status.into();
+    }
+
+    /// Returns the `status` field as a [`RecordStatus`].
+    /// An unrecognized value is mapped to [`RecordStatus::Unknown`].
+    ///
+    /// The raw string can be obtained by
+    /// [`ModelAccessor::status()`](zino_core::orm::ModelAccessor::status).
+    #[inline]
+    pub fn status(&self) -> RecordStatus {
+        self.status.as_str().into()
+    }
+
+    /// Logically deletes the record by setting the `status` as `Deleted`
+    /// and stamping the `deleted_at` field.
+    ///
+    /// The `version` and `updated_at` fields are bumped as a normal mutation, and the hooks
+    /// `before_soft_delete` and `after_soft_delete` are called. The record is reloaded
+    /// from the table on success.
+    #[cfg(feature = "soft-delete")]
+    pub async fn soft_delete(&mut self) -> Result<(), Error> {
+        let model_data = self.before_soft_delete().await?;
+        let query = self.current_version_query();
+        let mut mutation = self.soft_delete_mutation();
+        let ctx = Self::update_one(&query, &mut mutation).await?;
+        self.version_conflict().check(&ctx)?;
+        Self::after_soft_delete(&ctx, model_data).await?;
+        *self = Self::try_get_model(&self.id).await?;
+        Ok(())
+    }
+
+    /// Restores the logically deleted record by resetting the `status` as `Active`
+    /// and clearing the `deleted_at` field.
+    ///
+    /// The `version` and `updated_at` fields are bumped as a normal mutation, and the hooks
+    /// `before_restore` and `after_restore` are called. The record is reloaded
+    /// from the table on success.
+    #[cfg(feature = "soft-delete")]
+    pub async fn restore(&mut self) -> Result<(), Error> {
+        let model_data = self.before_restore().await?;
+        let query = self.current_version_query();
+        let mut mutation = self.restore_mutation();
+        let ctx = Self::update_one(&query, &mut mutation).await?;
+        self.version_conflict().check(&ctx)?;
+        Self::after_restore(&ctx, model_data).await?;
+        *self = Self::try_get_model(&self.id).await?;
+        Ok(())
+    }
+
+    /// Computes the `integrity` field over the canonical serialization of the `id`,
+    /// `recorded_at` and `extra` fields, and signs it into the `signature` field
+    /// if `database.integrity.signing-key` has been configured.
+    ///
+    /// It is called in `before_insert` before the fields of `extra` are encrypted.
+    pub fn seal(&mut self) {
+        self.integrity = self.integrity_digest();
+        if let Some(key) = Self::integrity_signing_key() {
+            self.signature = Self::sign_integrity(&self.integrity, key);
+        }
+    }
+
+    /// Verifies the `integrity` field by recomputing the digest.
+    ///
+    /// The fields of `extra` should have been decrypted.
+    pub fn verify_integrity(&self) -> Result<(), Error> {
+        if self.integrity != self.integrity_digest() {
+            bail!("the integrity of the record `{}` is broken", self.id);
+        }
+        Ok(())
+    }
+
+    /// Verifies the `signature` field of the `integrity` with the key,
+    /// which is derived from `database.integrity.signing-key` for the sealed records.
+    pub fn verify_signature(&self, key: &[u8]) -> Result<(), Error> {
+        if self.signature.is_empty() {
+            bail!("the record `{}` has not been signed", self.id);
+        }
+        Self::verify_integrity_signature(&self.integrity, &self.signature, key)
+    }
+
+    /// Computes the integrity digest of the record.
+    fn integrity_digest(&self) -> String {
+        let mut data = Map::new();
+        data.upsert("id", self.id.to_string());
+        data.upsert("recorded_at", self.recorded_at.timestamp_millis());
+        data.upsert("extra", self.extra.clone());
+        <Self as ModelHelper<Uuid>>::integrity_digest(&data, &["id", "recorded_at", "extra"])
+    }
+
+    /// Sets the `namespace` field, which is trimmed and converted to lowercase.
+    #[cfg(feature = "namespace")]
+    #[inline]
+    pub fn set_namespace(&mut self, namespace: &str) {
+        self.namespace = namespace.trim().to_lowercase();
+    }
+
+    /// Returns the `namespace` field.
+    #[cfg(feature = "namespace")]
+    #[inline]
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Constructs a default list query scoped by the namespace.
+    ///
+    /// The filters of the default list query are kept, so that the logically deleted
+    /// records are excluded unless the `show_deleted` flag is enabled.
+    #[cfg(feature = "namespace")]
+    pub fn namespace_query(namespace: &str) -> Query {
+        let mut query = Self::default_list_query();
+        query.add_filter("namespace", namespace.trim().to_lowercase());
+        query
+    }
+
+    /// Finds the records in the namespace, scoped by `before_list` with the extension.
+    #[cfg(feature = "namespace")]
+    pub async fn find_by_namespace(
+        namespace: &str,
+        extension: Option<&<Self as ModelHooks>::Extension>,
+    ) -> Result<Vec<Map>, Error> {
+        let mut query = Self::namespace_query(namespace);
+        Self::before_list(&mut query, extension).await?;
+        Self::find(&query).await
+    }
+
+    /// Counts the records in the namespace, scoped by `before_list` with the extension.
+    #[cfg(feature = "namespace")]
+    pub async fn count_by_namespace(
+        namespace: &str,
+        extension: Option<&<Self as ModelHooks>::Extension>,
+    ) -> Result<u64, Error> {
+        let mut query = Self::namespace_query(namespace);
+        Self::before_list(&mut query, extension).await?;
+        Self::count(&query).await
+    }
+
+    /// Constructs a full-text search query over the `name` and `description` fields,
+    /// ordered by the relevance if it is supported by the database.
+    ///
+    /// The query can be combined with other filters such as `status` or `namespace`.
+    #[inline]
+    pub fn search(terms: &str) -> Query {
+        Self::text_search_query(terms, &[])
+    }
+
+    /// Constructs a full-text search query restricted to the text search fields.
+    /// For MySQL, all the columns of the FULLTEXT index are searched as long as
+    /// any of them is in the fields.
+    #[inline]
+    pub fn search_in(terms: &str, fields: &[&str]) -> Query {
+        Self::text_search_query(terms, fields)
+    }
+
+    /// Returns the `location` field as `(lng, lat)`.
+    #[cfg(feature = "geo")]
+    #[inline]
+    pub fn location(&self) -> Option<(f64, f64)> {
+        self.location
+    }
+
+    /// Returns the `slug` field.
+    #[cfg(feature = "slug")]
+    #[inline]
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    /// Generates a unique `slug` from the `name` if it is empty.
+    /// The slugs of the records in the same batch to be inserted are regarded as taken.
+    #[cfg(feature = "slug")]
+    async fn generate_slug(&mut self, batch: &[Self]) -> Result<(), Error> {
+        if self.slug.is_empty() {
+            self.slug = slug::slugify(&self.name);
+            if self.slug.is_empty() {
+                self.slug = self.id.simple().to_string();
+            }
+        }
+
+        let base_slug = self.slug.clone();
+        let mut suffix = 1;
+        while batch
+            .iter()
+            .any(|record| record.slug_columns() == self.slug_columns())
+            || !self.is_unique_on(self.slug_columns()).await?
+        {
+            suffix += 1;
+            self.slug = slug::with_suffix(&base_slug, suffix);
+        }
+        Ok(())
+    }
+
+    /// Returns the column values which the `slug` should be unique on.
+    #[cfg(feature = "slug")]
+    fn slug_columns(&self) -> Vec<(&'static str, zino_core::JsonValue)> {
+        #[cfg(feature = "namespace")]
+        let columns = vec![
+            ("slug", self.slug.clone().into()),
+            ("namespace", self.namespace.clone().into()),
+        ];
+        #[cfg(not(feature = "namespace"))]
+        let columns = vec![("slug", self.slug.clone().into())];
+        columns
+    }
+
+    /// Returns the tenant ID of the user session.
+    #[cfg(feature = "tenant-id")]
+    fn current_tenant_id(session: Option<&UserSession<Uuid, String>>) -> Result<Uuid, Error> {
+        session
+            .and_then(|session| session.tenant_id())
+            .copied()
+            .ok_or_else(|| zino_core::warn!("403 Forbidden: the tenant context is missing"))
+    }
+
+    /// Imports a record from a json object produced by [`export()`](Self::export).
+    ///
+    /// # Note
+    ///
+    /// All the fields are restored verbatim, bypassing the read-only protections
+    /// of [`read_map()`](Model::read_map). It should never be used to handle untrusted input.
+    pub fn import(data: Map) -> Result<Self, Error> {
+        let record = Self::try_from_map(data)?;
+        if record.id.is_nil() {
+            bail!("the `id` of an imported record should not be nil");
+        }
+        Ok(record)
+    }
+}
+
+/// Returns the name of the JSON type.
+fn json_type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "a boolean",
+        JsonValue::Number(_) => "a number",
+        JsonValue::String(_) => "a string",
+        JsonValue::Array(_) => "an array",
+        JsonValue::Object(_) => "an object",
+    }
+}
+
+/// Merges the data into the map recursively.
+fn merge_maps(map: &mut Map, data: &Map) {
+    for (key, value) in data {
+        match (map.get_mut(key), value) {
+            (Some(JsonValue::Object(entry)), JsonValue::Object(value)) => merge_maps(entry, value),
+            _ => {
+                map.upsert(key, value.clone());
+            }
+        }
+    }
+}
+
 impl ModelHooks for Record {
     type Data = ();
-    #[cfg(feature = "maintainer-id")]
+    #[cfg(any(feature = "maintainer-id", feature = "tenant-id"))]
     type Extension = UserSession<Uuid, String>;
-    #[cfg(not(feature = "maintainer-id"))]
+    #[cfg(not(any(feature = "maintainer-id", feature = "tenant-id")))]
     type Extension = ();
 
+    async fn before_insert(&mut self) -> Result<Self::Data, Error> {
+        #[cfg(feature = "namespace")]
+        if self.namespace.is_empty() {
+            self.namespace = Self::model_namespace().to_owned();
+        }
+        #[cfg(feature = "slug")]
+        self.generate_slug(&[]).await?;
+        self.seal();
+        self.before_save().await
+    }
+
+    #[cfg(feature = "slug")]
+    async fn before_insert_many(models: &mut [Self]) -> Result<(), Error> {
+        for index in 0..models.len() {
+            let (batch, rest) = models.split_at_mut(index);
+            let record = &mut rest[0];
+            #[cfg(feature = "namespace")]
+            if record.namespace.is_empty() {
+                record.namespace = Self::model_namespace().to_owned();
+            }
+            record.generate_slug(batch).await?;
+        }
+        Ok(())
+    }
+
+    async fn before_save(&mut self) -> Result<Self::Data, Error> {
+        let mut data = Map::from_entry("extra", mem::take(&mut self.extra));
+        Self::encrypt_fields(&mut data)?;
+        if let Some(JsonValue::Object(extra)) = data.remove("extra") {
+            self.extra = extra;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    async fn after_decode(model: &mut Map) -> Result<(), Error> {
+        Self::decrypt_fields(model)
+    }
+
+    #[inline]
+    async fn before_list(
+        query: &mut Query,
+        extension: Option<&Self::Extension>,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "tenant-id")]
+        {
+            query.add_filter("tenant_id", Self::current_tenant_id(extension)?.to_string());
+            query.add_field("tenant_id");
+        }
+        #[cfg(not(feature = "tenant-id"))]
+        let _ = extension;
+        Self::encrypt_filters(query)
+    }
+
+    #[cfg(feature = "tenant-id")]
+    async fn before_insert_check(
+        &mut self,
+        extension: Option<&Self::Extension>,
+    ) -> Result<(), Error> {
+        self.tenant_id = Self::current_tenant_id(extension)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "tenant-id")]
+    async fn before_access(&self, extension: Option<&Self::Extension>) -> Result<(), Error> {
+        if self.tenant_id != Self::current_tenant_id(extension)? {
+            bail!("404 Not Found: cannot find the record `{}`", self.id);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "tenant-id")]
+    async fn before_respond(
+        model: &mut Map,
+        extension: Option<&Self::Extension>,
+    ) -> Result<(), Error> {
+        let tenant_id = Self::current_tenant_id(extension)?.to_string();
+        if model.get_str("tenant_id") != Some(tenant_id.as_str()) {
+            bail!("404 Not Found: cannot find the record");
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "maintainer-id")]
     #[inline]
     async fn after_extract(&mut self, session: Self::Extension) -> Result<(), Error> {
@@ -121,15 +813,320 @@ impl ModelHooks for Record {
         Ok(())
     }
 
-    #[cfg(feature = "maintainer-id")]
-    #[inline]
     async fn before_validation(
         data: &mut Map,
         extension: Option<&Self::Extension>,
     ) -> Result<(), Error> {
+        if let Some(JsonValue::String(name)) = data.get_mut("name") {
+            let trimmed_name = name.trim();
+            if trimmed_name.len() != name.len() {
+                *name = trimmed_name.to_owned();
+            }
+        }
+        #[cfg(feature = "namespace")]
+        if let Some(JsonValue::String(namespace)) = data.get_mut("namespace") {
+            *namespace = namespace.trim().to_lowercase();
+        }
+        #[cfg(feature = "maintainer-id")]
         if let Some(session) = extension {
             data.upsert("maintainer_id", session.user_id().to_string());
         }
+        #[cfg(not(feature = "maintainer-id"))]
+        let _ = extension;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Record;
+    use zino_core::{extension::JsonObjectExt, model::Model, orm::ModelHelper, Map};
+
+    #[test]
+    fn it_exports_and_imports_record() {
+        let mut record = Record::new();
+        let mut data = Map::new();
+        data.upsert("name", "backup");
+        data.upsert("description", "a record for the backup");
+        assert!(record.read_map(&data).is_success());
+        record.integrity = "sha256:0123456789abcdef".to_owned();
+        record.signature = "signed".to_owned();
+        record.version = 3;
+
+        let exported = record.export();
+        let imported = Record::import(exported.clone()).unwrap();
+        assert_eq!(imported.export(), exported);
+        assert_eq!(imported.integrity, record.integrity);
+
+        let mut invalid = exported;
+        invalid.upsert("id", zino_core::Uuid::nil().to_string());
+        assert!(Record::import(invalid).is_err());
+    }
+
+    #[test]
+    fn it_derives_etag_from_version() {
+        let mut record = Record::new();
+        record.version = 3;
+
+        let etag = record.etag();
+        assert!(etag.starts_with(&format!("\"{}-3-", record.id)));
+        assert_eq!(record.etag(), etag);
+
+        record.version = 4;
+        assert_ne!(record.etag(), etag);
+    }
+
+    #[test]
+    fn it_satisfies_if_match_with_etag() {
+        use std::{borrow::Cow, net::IpAddr};
+        use zino_core::{
+            error::Error,
+            request::{Context, RequestContext, Uri},
+            response::{Response, StatusCode},
+        };
+
+        struct TestRequest {
+            uri: Uri,
+            if_match: String,
+        }
+
+        impl RequestContext for TestRequest {
+            fn request_method(&self) -> &str {
+                "PUT"
+            }
+
+            fn original_uri(&self) -> &Uri {
+                &self.uri
+            }
+
+            fn matched_route(&self) -> Cow<'_, str> {
+                self.uri.path().into()
+            }
+
+            fn get_header(&self, name: &str) -> Option<&str> {
+                (name == "if-match").then_some(self.if_match.as_str())
+            }
+
+            fn client_ip(&self) -> Option<IpAddr> {
+                None
+            }
+
+            fn get_context(&self) -> Option<Context> {
+                None
+            }
+
+            fn get_data<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+                None
+            }
+
+            fn set_data<T: Clone + Send + Sync + 'static>(&mut self, _value: T) -> Option<T> {
+                None
+            }
+
+            async fn read_body_bytes(&mut self) -> Result<Vec<u8>, Error> {
+                Ok(Vec::new())
+            }
+        }
+
+        let record = Record::new();
+        let mut req = TestRequest {
+            uri: Uri::from_static("/record/update"),
+            if_match: record.etag(),
+        };
+        let mut res = Response::new(StatusCode::OK);
+        res.insert_header("etag", record.etag());
+        assert!(!res.evaluate_preconditions(&req));
+        assert_eq!(res.status_code(), 200);
+
+        req.if_match = record.etag().replace("-0-", "-1-");
+        let mut res = Response::new(StatusCode::OK);
+        res.insert_header("etag", record.etag());
+        assert!(res.evaluate_preconditions(&req));
+        assert_eq!(res.status_code(), 412);
+    }
+
+    #[test]
+    fn it_restores_record_from_snapshot() {
+        let mut record = Record::new();
+        record.name = "draft".to_owned();
+        record.extra.upsert("content", "v1");
+        record.version = 2;
+        let snapshot = record.snapshot();
+        assert_eq!(snapshot.get_str("model"), Some("record"));
+
+        record.name = "published".to_owned();
+        record.extra.upsert("content", "v2");
+        let created_at = record.created_at;
+        let mut forged = snapshot.clone();
+        if let Some(data) = forged.get_mut("data").and_then(|v| v.as_object_mut()) {
+            data.upsert("created_at", "2000-01-01T00:00:00Z");
+        }
+        assert!(record.restore_from(&forged).is_success());
+        assert_eq!(record.name, "draft");
+        assert_eq!(record.extra.get_str("content"), Some("v1"));
+        assert_eq!(record.version, 3);
+        assert_eq!(record.created_at, created_at);
+        assert!(record.verify_integrity().is_ok());
+
+        let mut other = Record::new();
+        assert!(!other.restore_from(&snapshot).is_success());
+        assert_eq!(other.version, 0);
+    }
+
+    #[test]
+    fn it_writes_read_only_content_through_state_mutation() {
+        let mut record = Record::new();
+        record.extra.upsert("content", "v1");
+        let snapshot = record.snapshot();
+        record.extra.upsert("content", "v2");
+        assert!(record.restore_from(&snapshot).is_success());
+        record.merge_content(&Map::from_entry("tags", vec!["a"]));
+        assert!(record.set_content_field("score", &1).is_success());
+        assert_eq!(record.extra.get_str("content"), Some("v1"));
+        assert_eq!(record.extra.len(), 3);
+
+        // The read-only fields skipped by `Schema::update()` are written by the mutation.
+        let mutation = record.state_mutation().unwrap();
+        let read_only_updates = mutation.read_only_updates();
+        let extra = read_only_updates.get_object("extra").unwrap();
+        assert_eq!(extra.get_str("content"), Some("v1"));
+        assert_eq!(extra.get_u64("score"), Some(1));
+        assert_eq!(extra.get_array("tags").map(|v| v.len()), Some(1));
+        assert_eq!(
+            read_only_updates.get_str("integrity"),
+            Some(record.integrity.as_str())
+        );
+        for field in ["signature", "recorded_at"] {
+            assert!(read_only_updates.contains_key(field));
+        }
+        assert_eq!(mutation.updates().get_u64("version"), Some(1));
+        assert!(!mutation.fields().iter().any(|field| field == "extra"));
+    }
+
+    #[cfg(feature = "slug")]
+    #[test]
+    fn it_indexes_record_slug() {
+        use zino_core::orm::Schema;
+
+        let index_type = Record::get_column("slug").and_then(|col| col.index_type());
+        if cfg!(feature = "namespace") {
+            assert_eq!(index_type, Some("btree"));
+        } else {
+            assert_eq!(index_type, Some("unique"));
+        }
+    }
+
+    #[test]
+    fn it_accesses_record_content_fields() {
+        let mut record = Record::new();
+        assert!(record.set_content_field("tags", &["a", "b"]).is_success());
+        assert!(!record.set_content_field("tags", &"a").is_success());
+        assert!(!record.set_content_field("", &1).is_success());
+
+        let tags = record
+            .content_field::<Vec<String>>("tags")
+            .unwrap()
+            .unwrap();
+        assert_eq!(tags, ["a", "b"]);
+        assert!(record.content_field::<u64>("tags").unwrap().is_err());
+        assert!(record.content_field::<u64>("missing").is_none());
+
+        record.extra.upsert("meta", Map::from_entry("x", 1));
+        let mut data = Map::from_entry("meta", Map::from_entry("y", 2));
+        data.upsert("version", 2);
+        record.merge_content(&data);
+        let meta = record.extra.get_object("meta").unwrap();
+        assert_eq!(meta.get_u64("x"), Some(1));
+        assert_eq!(meta.get_u64("y"), Some(2));
+        assert_eq!(record.extra.get_u64("version"), Some(2));
+        assert_eq!(record.extra.get_array("tags").map(|v| v.len()), Some(2));
+    }
+
+    #[test]
+    fn it_verifies_record_integrity() {
+        let mut record = Record::new();
+        record.extra.upsert("b", 2);
+        record.extra.upsert("a", Map::from_entry("y", 1));
+        record.seal();
+        assert!(record.integrity.starts_with("sha256:") || record.integrity.starts_with("sm3:"));
+        assert!(record.verify_integrity().is_ok());
+
+        let key = b"record-signing-key";
+        record.signature = Record::sign_integrity(&record.integrity, key);
+        assert!(record.verify_signature(key).is_ok());
+        assert!(record.verify_signature(b"another-key").is_err());
+
+        record.extra.upsert("b", 3);
+        assert!(record.verify_integrity().is_err());
+    }
+
+    #[cfg(not(feature = "slug"))]
+    #[test]
+    fn it_prepares_inserted_record() {
+        use zino_core::{model::ModelHooks, orm::Schema};
+
+        // The hook is run for the new rows of `bulk_upsert` as well.
+        let mut record = Record::new();
+        record.name = "upserted".to_owned();
+        futures::executor::block_on(record.before_insert()).unwrap();
+        #[cfg(feature = "namespace")]
+        assert_eq!(record.namespace, Record::model_namespace());
+        assert!(!record.integrity.is_empty());
+        assert!(record.verify_integrity().is_ok());
+    }
+
+    #[cfg(feature = "tenant-id")]
+    #[test]
+    fn it_scopes_batch_update_by_tenant() {
+        use zino_core::{auth::UserSession, model::ModelHooks, model::Query, Uuid};
+
+        let tenant_id = Uuid::now_v7();
+        let other_tenant_id = Uuid::now_v7();
+        let mut session = UserSession::new(Uuid::now_v7(), None);
+        session.set_tenant_id(tenant_id);
+
+        // The query by primary key is constructed in the same way as `batch_update`.
+        let mut query = Query::from_entry("id", Uuid::now_v7().to_string());
+        futures::executor::block_on(Record::before_list(&mut query, Some(&session))).unwrap();
+        assert_eq!(
+            query.filters().get_str("tenant_id"),
+            Some(tenant_id.to_string().as_str())
+        );
+
+        let mut query = Query::from_entry("id", Uuid::now_v7().to_string());
+        assert!(futures::executor::block_on(Record::before_list(&mut query, None)).is_err());
+
+        let mut model = Map::from_entry("tenant_id", other_tenant_id.to_string());
+        let result = Record::before_respond(&mut model, Some(&session));
+        assert!(futures::executor::block_on(result).is_err());
+
+        let mut model = Map::from_entry("name", "untenanted");
+        let result = Record::before_respond(&mut model, Some(&session));
+        assert!(futures::executor::block_on(result).is_err());
+
+        let mut model = Map::from_entry("tenant_id", tenant_id.to_string());
+        let result = Record::before_respond(&mut model, Some(&session));
+        assert!(futures::executor::block_on(result).is_ok());
+    }
+
+    #[cfg(feature = "soft-delete")]
+    #[test]
+    fn it_stamps_deleted_at_in_soft_delete_mutation() {
+        use zino_core::orm::ModelAccessor;
+
+        let record = Record::new();
+        let mutation = record.soft_delete_mutation();
+        assert_eq!(mutation.updates().get_str("status"), Some("Deleted"));
+        assert!(mutation
+            .read_only_updates()
+            .get("deleted_at")
+            .is_some_and(|value| !value.is_null()));
+
+        let mutation = record.restore_mutation();
+        assert_eq!(mutation.updates().get_str("status"), Some("Active"));
+        assert!(mutation
+            .read_only_updates()
+            .get("deleted_at")
+            .is_some_and(|value| value.is_null()));
+    }
+}
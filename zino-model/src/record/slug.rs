@@ -0,0 +1,44 @@
+use deunicode::deunicode;
+
+/// Generates a URL-friendly slug from the name.
+///
+/// The name is transliterated into ASCII, lowercased, and every run of
+/// non-alphanumeric characters is replaced by a single hyphen.
+pub(super) fn slugify(name: &str) -> String {
+    let ascii_name = deunicode(name);
+    let mut slug = String::with_capacity(ascii_name.len());
+    let mut pending_hyphen = false;
+    for ch in ascii_name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            if pending_hyphen && !slug.is_empty() {
+                slug.push('-');
+            }
+            slug.push(ch.to_ascii_lowercase());
+            pending_hyphen = false;
+        } else {
+            pending_hyphen = true;
+        }
+    }
+    slug
+}
+
+/// Appends a numeric suffix to the slug for the collision handling.
+#[inline]
+pub(super) fn with_suffix(slug: &str, suffix: usize) -> String {
+    format!("{slug}-{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{slugify, with_suffix};
+
+    #[test]
+    fn it_generates_slugs() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Crème Brûlée  "), "creme-brulee");
+        assert_eq!(slugify("Straße--42"), "strasse-42");
+        assert_eq!(slugify("北京"), "bei-jing");
+        assert_eq!(slugify("***"), "");
+        assert_eq!(with_suffix("hello-world", 2), "hello-world-2");
+    }
+}
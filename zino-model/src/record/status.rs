@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, fmt, str::FromStr};
+use zino_core::JsonValue;
+
+/// Record status.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum RecordStatus {
+    /// It indicates that the record is in use.
+    /// This is the default value.
+    #[default]
+    Active,
+    /// It indicates that the record has not been activated.
+    Inactive,
+    /// It indicates that the record has been locked and cannot be modified.
+    Locked,
+    /// It indicates that the record has been soft deleted.
+    Deleted,
+    /// It indicates that the record has been archived and cannot be modified.
+    Archived,
+    /// An unknown status loaded from the database.
+    Unknown(String),
+}
+
+impl RecordStatus {
+    /// Returns the string representation of the status.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Active => "Active",
+            Self::Inactive => "Inactive",
+            Self::Locked => "Locked",
+            Self::Deleted => "Deleted",
+            Self::Archived => "Archived",
+            Self::Unknown(status) => status.as_str(),
+        }
+    }
+
+    /// Returns `true` if the status is unknown.
+    #[inline]
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(_))
+    }
+}
+
+impl AsRef<str> for RecordStatus {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for RecordStatus {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RecordStatus {
+    type Err = Infallible;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl From<&str> for RecordStatus {
+    fn from(value: &str) -> Self {
+        const STATUSES: [RecordStatus; 5] = [
+            RecordStatus::Active,
+            RecordStatus::Inactive,
+            RecordStatus::Locked,
+            RecordStatus::Deleted,
+            RecordStatus::Archived,
+        ];
+        STATUSES
+            .into_iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(value))
+            .unwrap_or_else(|| Self::Unknown(value.to_owned()))
+    }
+}
+
+impl From<String> for RecordStatus {
+    #[inline]
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl From<RecordStatus> for String {
+    #[inline]
+    fn from(value: RecordStatus) -> Self {
+        match value {
+            RecordStatus::Unknown(status) => status,
+            _ => value.as_str().to_owned(),
+        }
+    }
+}
+
+impl From<RecordStatus> for JsonValue {
+    #[inline]
+    fn from(value: RecordStatus) -> Self {
+        String::from(value).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecordStatus;
+
+    #[test]
+    fn it_parses_record_status() {
+        assert_eq!(RecordStatus::from("locked"), RecordStatus::Locked);
+        assert_eq!(RecordStatus::from("Archived").to_string(), "Archived");
+
+        let status = RecordStatus::from("Reviewing");
+        assert!(status.is_unknown());
+        assert_eq!(status.as_str(), "Reviewing");
+    }
+}
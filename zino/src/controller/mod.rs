@@ -120,7 +120,12 @@ where
 
     async fn delete(req: Self::Request) -> Self::Result {
         let id = req.parse_param::<K>("id")?;
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         let model = Self::try_get_model(&id).await.extract(&req)?;
+        model
+            .before_access(extension.as_ref())
+            .await
+            .extract(&req)?;
         model.delete().await.extract(&req)?;
 
         let res = Response::default().context(&req);
@@ -132,12 +137,18 @@ where
         let mut body = req.parse_body().await?;
 
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
-        let (validation, model) = Self::update_by_id(&id, &mut body, extension)
-            .await
-            .extract(&req)?;
+        let (validation, model, modified) =
+            Self::update_by_id_if_modified(&id, &mut body, extension)
+                .await
+                .extract(&req)?;
         let mut res = Response::from(validation).context(&req);
         if res.is_success() {
-            let model_filters = model.next_version_filters();
+            let model_filters = if modified {
+                model.next_version_filters()
+            } else {
+                res.set_message("the model is not modified");
+                model.current_version_filters()
+            };
             res.set_json_data(Self::data_item(model_filters));
         }
         Ok(res.into())
@@ -147,13 +158,17 @@ where
         let id = req.parse_param::<K>("id")?;
         let extension = req.get_data::<<Self as ModelHooks>::Extension>();
         let mut model = if req.get_query("fetch") == Some("false") {
-            Self::find_by_id(&id).await.extract(&req)?
+            let no_cache = req.get_query("no_cache").is_some_and(|s| s != "false");
+            Self::find_cached_by_id(&id, no_cache).await.extract(&req)?
         } else {
             Self::fetch_by_id(&id).await.extract(&req)?
         };
         Self::before_respond(&mut model, extension.as_ref())
             .await
             .extract(&req)?;
+        Self::after_read(std::slice::from_ref(&model), extension.as_ref())
+            .await
+            .extract(&req)?;
 
         let mut res = Response::default().context(&req);
         res.set_json_data(Self::data_item(model));
@@ -181,7 +196,7 @@ where
             }
             models
         } else {
-            let mut models = Self::find(&query).await.extract(&req)?;
+            let mut models = Self::find_cached(&query).await.extract(&req)?;
             let translate_enabled = query.translate_enabled();
             for model in models.iter_mut() {
                 Self::after_decode(model).await.extract(&req)?;
@@ -192,6 +207,9 @@ where
             }
             models
         };
+        Self::after_read(&models, extension.as_ref())
+            .await
+            .extract(&req)?;
 
         let mut data = Self::data_items(models);
         if let Some(page_size) = req.get_query("page_size").and_then(|s| s.parse().ok()) {
@@ -223,6 +241,9 @@ where
                 .await
                 .extract(&req)?;
         }
+        Self::after_read(&models, extension.as_ref())
+            .await
+            .extract(&req)?;
 
         let mut data = Self::data_items(models);
         if let Some(page_size) = req.get_query("page_size").and_then(|s| s.parse().ok()) {
@@ -239,6 +260,13 @@ where
 
     async fn soft_delete(req: Self::Request) -> Self::Result {
         let id = req.parse_param::<K>("id")?;
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        Self::try_get_model(&id)
+            .await
+            .extract(&req)?
+            .before_access(extension.as_ref())
+            .await
+            .extract(&req)?;
         Self::soft_delete_by_id(&id).await.extract(&req)?;
 
         let res = Response::default().context(&req);
@@ -247,6 +275,13 @@ where
 
     async fn lock(req: Self::Request) -> Self::Result {
         let id = req.parse_param::<K>("id")?;
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        Self::try_get_model(&id)
+            .await
+            .extract(&req)?
+            .before_access(extension.as_ref())
+            .await
+            .extract(&req)?;
         Self::lock_by_id(&id).await.extract(&req)?;
 
         let res = Response::default().context(&req);
@@ -255,6 +290,13 @@ where
 
     async fn archive(req: Self::Request) -> Self::Result {
         let id = req.parse_param::<K>("id")?;
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
+        Self::try_get_model(&id)
+            .await
+            .extract(&req)?
+            .before_access(extension.as_ref())
+            .await
+            .extract(&req)?;
         Self::archive_by_id(&id).await.extract(&req)?;
 
         let res = Response::default().context(&req);
@@ -333,13 +375,18 @@ where
 
     async fn batch_update(mut req: Self::Request) -> Self::Result {
         let data = req.parse_body::<Vec<Map>>().await?;
+        let extension = req.get_data::<<Self as ModelHooks>::Extension>();
 
         // Should use `Self::transaction` when the `Send` bound is resolved
         let primary_key_name = Self::PRIMARY_KEY_NAME;
         let mut rows_affected = 0;
         for mut map in data.into_iter() {
             if let Some(id) = map.remove(primary_key_name) {
-                let query = Query::from_entry(primary_key_name, id);
+                let mut query = Query::from_entry(primary_key_name, id);
+                Self::before_list(&mut query, extension.as_ref())
+                    .await
+                    .extract(&req)?;
+
                 let mut mutation = Mutation::new(map);
                 let ctx = Self::update_one(&query, &mut mutation)
                     .await
@@ -465,6 +512,9 @@ where
                 .await
                 .extract(&req)?;
         }
+        Self::after_read(&models, extension.as_ref())
+            .await
+            .extract(&req)?;
 
         let format = req.get_query("format").unwrap_or("json");
         match format {
@@ -528,6 +578,9 @@ where
             }
             model.upsert("children", model_children);
         }
+        Self::after_read(&models, extension.as_ref())
+            .await
+            .extract(&req)?;
 
         let mut data = Self::data_items(models);
         data.upsert("total_rows", total_rows);
@@ -1,12 +1,20 @@
 use axum::{
-    body::Body,
+    body::{Body, Bytes},
     http::{
         header::{self, HeaderName, HeaderValue},
-        StatusCode,
+        HeaderMap, StatusCode,
     },
     response::IntoResponse,
 };
-use zino_core::response::{Rejection, Response, ResponseCode};
+use futures::{StreamExt, TryStreamExt};
+use http_body::Frame;
+use hyper::ext::ReasonPhrase;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use zino_core::response::{BodyStream, Rejection, Response, ResponseCode, Trailers};
 
 /// An HTTP response for `axum`.
 pub struct AxumResponse<S: ResponseCode = StatusCode>(Response<S>);
@@ -46,26 +54,110 @@ impl IntoResponse for AxumRejection {
 pub(crate) fn build_http_response<S: ResponseCode>(
     mut response: Response<S>,
 ) -> axum::response::Response {
-    let mut res = match response.read_bytes() {
-        Ok(data) => axum::response::Response::builder()
-            .status(response.status_code())
+    let status_code =
+        StatusCode::from_u16(response.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut res = if let Some(stream) = response.take_body_stream() {
+        let body = if let Some(trailers) = response.take_trailers() {
+            let names = trailers.names().collect::<Vec<_>>().join(", ");
+            response.insert_header("trailer", names);
+            Body::new(TrailersBody {
+                stream,
+                trailers: Some(trailers),
+                done: false,
+            })
+        } else {
+            Body::from_stream(stream.map_err(|err| io::Error::other(err.to_string())))
+        };
+        axum::response::Response::builder()
+            .status(status_code)
             .header(header::CONTENT_TYPE, response.content_type())
-            .body(Body::from(data))
-            .unwrap_or_default(),
-        Err(err) => axum::response::Response::builder()
-            .status(S::INTERNAL_SERVER_ERROR.status_code())
-            .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
-            .body(Body::from(err.to_string()))
-            .unwrap_or_default(),
+            .body(body)
+            .unwrap_or_default()
+    } else {
+        match response.read_bytes() {
+            Ok(data) => axum::response::Response::builder()
+                .status(status_code)
+                .header(header::CONTENT_TYPE, response.content_type())
+                .body(Body::from(data))
+                .unwrap_or_default(),
+            Err(err) => axum::response::Response::builder()
+                .status(S::INTERNAL_SERVER_ERROR.status_code())
+                .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(Body::from(err.to_string()))
+                .unwrap_or_default(),
+        }
     };
 
+    if res.status() == status_code {
+        if let Some(reason) = response.reason_phrase() {
+            if let Ok(reason) = ReasonPhrase::try_from(reason.as_bytes()) {
+                res.extensions_mut().insert(reason);
+            }
+        }
+    }
+
     for (key, value) in response.finalize() {
         if let Ok(header_name) = HeaderName::try_from(key.as_ref()) {
             if let Ok(header_value) = HeaderValue::try_from(value) {
-                res.headers_mut().insert(header_name, header_value);
+                if key == "set-cookie" {
+                    res.headers_mut().append(header_name, header_value);
+                } else {
+                    res.headers_mut().insert(header_name, header_value);
+                }
             }
         }
     }
 
     res
 }
+
+/// A streaming body with the trailers computed at the end of the stream.
+struct TrailersBody {
+    /// Body stream.
+    stream: BodyStream,
+    /// Trailers.
+    trailers: Option<Trailers>,
+    /// A flag to determine whether the stream has been exhausted.
+    done: bool,
+}
+
+impl http_body::Body for TrailersBody {
+    type Data = Bytes;
+    type Error = io::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        match self.stream.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(data))) => Poll::Ready(Some(Ok(Frame::data(data)))),
+            Poll::Ready(Some(Err(err))) => {
+                Poll::Ready(Some(Err(io::Error::other(err.to_string()))))
+            }
+            Poll::Ready(None) => {
+                self.done = true;
+                let Some(trailers) = self.trailers.take() else {
+                    return Poll::Ready(None);
+                };
+                let mut headers = HeaderMap::new();
+                for (key, value) in trailers.compute() {
+                    if let Ok(header_name) = HeaderName::try_from(key.as_ref()) {
+                        if let Ok(header_value) = HeaderValue::try_from(value) {
+                            headers.insert(header_name, header_value);
+                        }
+                    }
+                }
+                Poll::Ready(Some(Ok(Frame::trailers(headers))))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.done
+    }
+}
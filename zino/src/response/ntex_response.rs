@@ -1,12 +1,13 @@
+use futures::StreamExt;
 use ntex::{
     http::{
-        body::Body,
+        body::{Body, BodyStream, SizedStream},
         header::{self, HeaderName, HeaderValue},
         ResponseError, StatusCode,
     },
     web::{HttpRequest, HttpResponse, Responder, WebResponseError},
 };
-use std::fmt;
+use std::{error::Error, fmt, io, rc::Rc};
 use zino_core::{
     response::{Rejection, Response, ResponseCode},
     trace::TimingMetric,
@@ -34,7 +35,11 @@ impl<S: ResponseCode> Responder for NtexResponse<S> {
         for (key, value) in response.finalize() {
             if let Ok(header_name) = HeaderName::try_from(key.as_ref()) {
                 if let Ok(header_value) = HeaderValue::try_from(value) {
-                    res.headers_mut().insert(header_name, header_value);
+                    if key == "set-cookie" {
+                        res.headers_mut().append(header_name, header_value);
+                    } else {
+                        res.headers_mut().insert(header_name, header_value);
+                    }
                 }
             }
         }
@@ -97,6 +102,20 @@ impl ResponseError for NtexRejection {
         for (key, value) in response.headers() {
             if let Ok(header_name) = HeaderName::try_from(key.as_ref()) {
                 if let Ok(header_value) = HeaderValue::try_from(value) {
+                    if key == "set-cookie" {
+                        res.headers_mut().append(header_name, header_value);
+                    } else {
+                        res.headers_mut().insert(header_name, header_value);
+                    }
+                }
+            }
+        }
+        for (key, value) in response.encoded_headers() {
+            if let Ok(header_value) = HeaderValue::try_from(value) {
+                let header_name = HeaderName::from_static(key);
+                if *key == "vary" {
+                    res.headers_mut().append(header_name, header_value);
+                } else {
                     res.headers_mut().insert(header_name, header_value);
                 }
             }
@@ -115,6 +134,36 @@ impl WebResponseError for NtexRejection {
 
 /// Build http response from `zino_core::response::Response`.
 fn build_http_response<S: ResponseCode>(response: &mut Response<S>) -> HttpResponse {
+    if let Some(stream) = response.take_body_stream() {
+        if let Some(trailers) = response.take_trailers() {
+            let names = trailers.names().collect::<Vec<_>>().join(", ");
+            tracing::warn!(names, "trailers are not supported for the streaming body");
+        }
+        let status_code = response
+            .status_code()
+            .try_into()
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let stream = stream.map(|result| {
+            result
+                .map(|chunk| chunk.to_vec().into())
+                .map_err(|err| io::Error::other(err.to_string()))
+        });
+        let content_length = response
+            .get_header("content-length")
+            .and_then(|s| s.parse::<u64>().ok());
+        let body = if let Some(content_length) = content_length {
+            let stream = stream.map(|result| result.map_err(|err| Rc::new(err) as Rc<dyn Error>));
+            Body::from_message(SizedStream::new(content_length, stream))
+        } else {
+            Body::from_message(BodyStream::new(stream))
+        };
+        let mut res = HttpResponse::with_body(status_code, body);
+        res.head_mut().reason = response.reason_phrase();
+        if let Ok(header_value) = HeaderValue::try_from(response.content_type()) {
+            res.headers_mut().insert(header::CONTENT_TYPE, header_value);
+        }
+        return res;
+    }
     match response.read_bytes() {
         Ok(data) => {
             let status_code = response
@@ -123,6 +172,7 @@ fn build_http_response<S: ResponseCode>(response: &mut Response<S>) -> HttpRespo
                 .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
             let body = Body::from(data.to_vec());
             let mut res = HttpResponse::with_body(status_code, body);
+            res.head_mut().reason = response.reason_phrase();
             if let Ok(header_value) = HeaderValue::try_from(response.content_type()) {
                 res.headers_mut().insert(header::CONTENT_TYPE, header_value);
             }